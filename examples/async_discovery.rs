@@ -0,0 +1,30 @@
+//! Listen for [FlexRadio Discovery packets] on port 4992 with tokio and print the contents
+//! of their data payloads.
+//!
+//! [FlexRadio Discovery packets]: https://github.com/flexradio/smartsdr-api-docs/wiki/Discovery-protocol
+
+use futures::StreamExt;
+use vrt::VrtUdpStream;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), vrt::Error> {
+    // Bind the UDP socket to port 4992 on all available interfaces
+    let mut stream = VrtUdpStream::bind("0.0.0.0:4992").await?;
+    println!("Listening on {}", stream.get_ref().local_addr()?);
+
+    while let Some(packet) = stream.next().await {
+        match packet {
+            Ok(packet) => {
+                println!(
+                    "Received packet from {:?}: {:?}",
+                    stream.source_addr(),
+                    String::from_utf8_lossy(&packet.payload)
+                );
+            }
+            Err(e) => {
+                println!("Failed to receive packet: {}", e);
+            }
+        }
+    }
+    Ok(())
+}
@@ -21,15 +21,15 @@ fn main() -> std::io::Result<()> {
         println!("Received {} bytes from {}", len, src);
 
         // Send a reply back to the source
-        match VrtPacket::parse(&buf[..len]) {
-            Ok((_rest, packet)) => {
+        match VrtPacket::from_bytes(&buf[..len]) {
+            Ok((packet, _len)) => {
                 println!(
                     "Received packet: {:?}",
                     String::from_utf8_lossy(packet.payload)
                 );
             }
             Err(e) => {
-                println!("Failed to parse packet: {:?}", e);
+                println!("Failed to parse packet: {}", e);
             }
         }
     }
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vrt::ClassId;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((_, value)) = ClassId::parse(data) {
+        let mut buf = [0; ClassId::SIZE];
+        value.encode(&mut buf).unwrap();
+        assert_eq!(ClassId::parse(&buf), Ok((&[][..], value)));
+    }
+});
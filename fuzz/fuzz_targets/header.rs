@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vrt::Header;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((_, value)) = Header::parse(data) {
+        let mut buf = [0; Header::SIZE];
+        value.encode(&mut buf).unwrap();
+        assert_eq!(Header::parse(&buf), Ok((&[][..], value)));
+    }
+});
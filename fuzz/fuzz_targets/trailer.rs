@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vrt::Trailer;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((_, value)) = Trailer::parse(data) {
+        let mut buf = [0; Trailer::SIZE];
+        value.encode(&mut buf).unwrap();
+        assert_eq!(Trailer::parse(&buf), Ok((&[][..], value)));
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vrt::{CommandPacket, ContextPacket, VrtPacket};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((_, packet)) = VrtPacket::parse(data) {
+        let _ = packet.encode_to_vec();
+        let _ = ContextPacket::try_from(packet.clone());
+        let _ = CommandPacket::try_from(packet);
+    }
+});
@@ -10,4 +10,136 @@ pub enum Error {
     /// Invalid packet type.
     #[error("Invalid Packet Type: {0}")]
     PktType(u8),
+    /// The output buffer is too small to hold the encoded data.
+    #[error("Buffer too small: {needed} bytes needed, {available} available")]
+    BufferTooSmall {
+        /// Number of bytes required.
+        needed: usize,
+        /// Number of bytes available.
+        available: usize,
+    },
+    /// The payload length is not a multiple of 32-bit words.
+    #[error("Payload length {0} is not a multiple of 4 bytes")]
+    PayloadAlignment(usize),
+    /// The packet does not fit in the 16-bit packet size field.
+    #[error("Packet size of {0} words exceeds the maximum of 65535")]
+    PacketTooLarge(usize),
+    /// The VRL frame does not fit in the 20-bit frame size field.
+    #[error("Frame size of {0} words exceeds the maximum of 1048575")]
+    FrameTooLarge(usize),
+    /// The header indicator bits disagree with the optional fields present in the packet.
+    #[error("Header does not match packet contents: {0}")]
+    HeaderMismatch(&'static str),
+    /// Invalid real/complex type.
+    #[error("Invalid Real/Complex Type: {0}")]
+    RealComplexType(u8),
+    /// Invalid data item format.
+    #[error("Invalid Data Item Format: {0}")]
+    DataItemFormat(u8),
+    /// The item packing field is too small to hold the data item and its tags.
+    #[error("Item packing field size {0} is too small for the data item and tags")]
+    ItemPackingFieldSize(u8),
+    /// Invalid command action mode.
+    #[error("Invalid Action Mode: {0}")]
+    ActionMode(u8),
+    /// The input ends before the end of the packet or frame.
+    #[error("Truncated input: {needed} more bytes needed")]
+    Truncated {
+        /// Number of additional bytes needed, or 1 if unknown.
+        needed: usize,
+    },
+    /// The packet or frame size is too small to hold the fields it must contain.
+    #[error("Size of {size} bytes is less than the {min_size} bytes required by its fields")]
+    SizeMismatch {
+        /// Size given by the size field, in bytes.
+        size: usize,
+        /// Size of the fields, in bytes.
+        min_size: usize,
+    },
+    /// A field holds an invalid value or extends past the end of the packet.
+    #[error("Invalid {field} at byte offset {offset}")]
+    InvalidField {
+        /// Name of the field.
+        field: &'static str,
+        /// Byte offset of the failure from the start of the packet or frame.
+        offset: usize,
+    },
+    /// The packet type is reserved or not the one expected.
+    #[error("Unsupported Packet Type: {0}")]
+    UnsupportedPacketType(u8),
+    /// The context packet uses VITA 49.2 Field Attributes (CIF7), which are not supported.
+    #[error("VITA 49.2 Field Attributes (CIF7) are not supported")]
+    FieldAttributes,
+    /// The VRL frame CRC does not match the frame contents.
+    #[error("CRC mismatch: frame carries {received:#010x}, computed {computed:#010x}")]
+    CrcMismatch {
+        /// CRC carried in the frame trailer.
+        received: u32,
+        /// CRC computed over the frame.
+        computed: u32,
+    },
+    /// An I/O operation failed.
+    #[cfg(feature = "std")]
+    #[error("I/O error: {}", io_error(.kind, .os_error))]
+    Io {
+        /// Kind of the I/O error.
+        kind: std::io::ErrorKind,
+        /// Error code from the operating system, if the error came from it.
+        os_error: Option<i32>,
+    },
+}
+
+impl Error {
+    /// Convert the failure of a nom parser on `input` into an error for `field`.
+    pub(crate) fn from_nom(
+        input: &[u8],
+        e: nom::Err<nom::error::Error<&[u8]>>,
+        field: &'static str,
+    ) -> Self {
+        match e {
+            nom::Err::Incomplete(nom::Needed::Size(needed)) => Error::Truncated {
+                needed: needed.get(),
+            },
+            nom::Err::Incomplete(nom::Needed::Unknown) => Error::Truncated { needed: 1 },
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                // The failing input is a subslice of `input`, so its address gives the offset.
+                let offset = (e.input.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+                Error::InvalidField {
+                    field,
+                    offset: if offset <= input.len() { offset } else { 0 },
+                }
+            }
+        }
+    }
+
+    /// Add `base` to the offset of an [`Error::InvalidField`], for a field parsed from input that
+    /// starts `base` bytes into the packet or frame.
+    pub(crate) fn offset_by(self, base: usize) -> Self {
+        match self {
+            Error::InvalidField { field, offset } => Error::InvalidField {
+                field,
+                offset: base + offset,
+            },
+            e => e,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io {
+            kind: e.kind(),
+            os_error: e.raw_os_error(),
+        }
+    }
+}
+
+/// Rebuild the I/O error of an [`Error::Io`], to display the operating system message.
+#[cfg(feature = "std")]
+fn io_error(kind: &std::io::ErrorKind, os_error: &Option<i32>) -> std::io::Error {
+    match *os_error {
+        Some(code) => std::io::Error::from_raw_os_error(code),
+        None => std::io::Error::from(*kind),
+    }
 }
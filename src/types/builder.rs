@@ -0,0 +1,131 @@
+use super::*;
+use crate::Error;
+
+/// Builder for [`VrtPacket`]s that derives the header fields from the packet contents.
+///
+/// ```
+/// use vrt::{PktType, Tsf, Tsi, VrtPacketBuilder};
+///
+/// let payload = [0u8; 8];
+/// let packet = VrtPacketBuilder::new(PktType::IfDataWithStream)
+///     .stream_id(0x1234)
+///     .tsi(Tsi::Utc, 1_700_000_000)
+///     .tsf(Tsf::RealTime, 0)
+///     .payload(&payload)
+///     .build()
+///     .unwrap();
+/// assert_eq!(packet.header.packet_size, 7);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct VrtPacketBuilder<'a> {
+    packet_type: PktType,
+    packet_count: u8,
+    indicators: Option<Indicators>,
+    stream_id: Option<u32>,
+    class_id: Option<ClassId>,
+    tsi: Option<(Tsi, u32)>,
+    tsf: Option<(Tsf, u64)>,
+    payload: &'a [u8],
+    trailer: Option<Trailer>,
+}
+
+impl<'a> VrtPacketBuilder<'a> {
+    /// Start building a packet of the given type.
+    pub fn new(packet_type: PktType) -> Self {
+        VrtPacketBuilder {
+            packet_type,
+            packet_count: 0,
+            indicators: None,
+            stream_id: None,
+            class_id: None,
+            tsi: None,
+            tsf: None,
+            payload: &[],
+            trailer: None,
+        }
+    }
+
+    /// Set the modulo-16 packet count.
+    pub fn packet_count(mut self, packet_count: u8) -> Self {
+        self.packet_count = packet_count & 0xf;
+        self
+    }
+
+    /// Set the packet-specific indicator bits. The trailer indicator of data packets is always
+    /// derived from the presence of a trailer.
+    pub fn indicators(mut self, indicators: Indicators) -> Self {
+        self.indicators = Some(indicators);
+        self
+    }
+
+    /// Set the Stream Identifier.
+    pub fn stream_id(mut self, stream_id: u32) -> Self {
+        self.stream_id = Some(stream_id);
+        self
+    }
+
+    /// Set the Class Identifier.
+    pub fn class_id(mut self, class_id: ClassId) -> Self {
+        self.class_id = Some(class_id);
+        self
+    }
+
+    /// Set the Integer-seconds Timestamp. Passing [`Tsi::None`] removes the timestamp.
+    pub fn tsi(mut self, tsi: Tsi, value: u32) -> Self {
+        self.tsi = (tsi != Tsi::None).then_some((tsi, value));
+        self
+    }
+
+    /// Set the Fractional-seconds Timestamp. Passing [`Tsf::None`] removes the timestamp.
+    pub fn tsf(mut self, tsf: Tsf, value: u64) -> Self {
+        self.tsf = (tsf != Tsf::None).then_some((tsf, value));
+        self
+    }
+
+    /// Set the data payload. Its length must be a multiple of 32-bit words.
+    pub fn payload(mut self, payload: &'a [u8]) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// Set the VRT Packet Trailer.
+    pub fn trailer(mut self, trailer: Trailer) -> Self {
+        self.trailer = Some(trailer);
+        self
+    }
+
+    /// Build the packet, computing the header indicator bits and packet size.
+    pub fn build(self) -> Result<VrtPacket<'a>, Error> {
+        let mut indicators = self
+            .indicators
+            .unwrap_or_else(|| Indicators::new(self.packet_type));
+        if let Indicators::Data { trailer, .. } = &mut indicators {
+            *trailer = self.trailer.is_some();
+        }
+
+        let mut packet = VrtPacket {
+            header: Header {
+                packet_type: self.packet_type,
+                c: self.class_id.is_some(),
+                indicators,
+                tsi: self.tsi.map_or(Tsi::None, |(tsi, _)| tsi),
+                tsf: self.tsf.map_or(Tsf::None, |(tsf, _)| tsf),
+                packet_count: self.packet_count,
+                packet_size: 0,
+            },
+            stream_id: self.stream_id,
+            class_id: self.class_id,
+            tsi: self.tsi.map(|(_, value)| value),
+            tsf: self.tsf.map(|(_, value)| value),
+            payload: self.payload,
+            trailer: self.trailer,
+        };
+
+        let words = packet.encoded_len() / size_of::<u32>();
+        packet.header.packet_size =
+            u16::try_from(words).map_err(|_| Error::PacketTooLarge(words))?;
+        packet.check_header()?;
+
+        Ok(packet)
+    }
+}
@@ -0,0 +1,625 @@
+use alloc::vec::Vec;
+use core::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use nom::{
+    bytes::complete::take,
+    error::ErrorKind,
+    number::{
+        complete::{be_u128, be_u16, be_u32, be_u8, u16, u32, u64},
+        Endianness,
+    },
+    Err, IResult, Parser,
+};
+
+use super::*;
+use crate::Error;
+
+/// Link-layer header types, from the tcpdump.org registry.
+const LINKTYPE_NULL: u16 = 0;
+const LINKTYPE_ETHERNET: u16 = 1;
+const LINKTYPE_RAW: u16 = 101;
+const LINKTYPE_LINUX_SLL: u16 = 113;
+const LINKTYPE_IPV4: u16 = 228;
+const LINKTYPE_IPV6: u16 = 229;
+const LINKTYPE_LINUX_SLL2: u16 = 276;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_QINQ: u16 = 0x88a8;
+
+const IP_PROTOCOL_TCP: u8 = 6;
+const IP_PROTOCOL_UDP: u8 = 17;
+
+/// pcapng block types.
+const BLOCK_SECTION_HEADER: u32 = 0x0a0d_0d0a;
+const BLOCK_INTERFACE_DESCRIPTION: u32 = 1;
+const BLOCK_ENHANCED_PACKET: u32 = 6;
+
+/// pcapng interface description options.
+const OPTION_END: u16 = 0;
+const OPTION_IF_TSRESOL: u16 = 9;
+const OPTION_IF_TSOFFSET: u16 = 14;
+
+/// Packet capture file in the pcap or pcapng format, such as those written by Wireshark and
+/// tcpdump.
+///
+/// ```no_run
+/// use vrt::Capture;
+///
+/// let data = std::fs::read("recording.pcapng").unwrap();
+/// let capture = Capture::new(&data).unwrap();
+/// for packet in capture.vrt_packets().port(4991) {
+///     let (timestamp, packet) = packet.unwrap();
+///     println!("{:?}: {:?}", timestamp, packet.header.packet_type);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Capture<'a> {
+    records: &'a [u8],
+    format: Format,
+}
+
+/// Record format of a capture file.
+#[derive(Clone, Debug)]
+enum Format {
+    Pcap {
+        endian: Endianness,
+        nanoseconds: bool,
+        link_type: u16,
+    },
+    Pcapng {
+        endian: Endianness,
+        interfaces: Vec<Interface>,
+    },
+}
+
+/// Capture interface of a pcapng section.
+#[derive(Clone, Copy, Debug)]
+struct Interface {
+    link_type: u16,
+    units_per_second: u128,
+    offset: i64,
+}
+
+/// Frame captured on a network link.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CapturedFrame<'a> {
+    /// Capture time since the UNIX epoch
+    pub timestamp: Duration,
+    /// Link-layer header type of the frame, from the tcpdump.org registry
+    pub link_type: u16,
+    /// Captured bytes of the frame
+    pub data: &'a [u8],
+    /// Length of the frame on the link, which is larger than `data` if the capture was truncated
+    pub original_len: u32,
+}
+
+/// Transport protocol of a captured payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Transport {
+    /// User Datagram Protocol
+    Udp,
+    /// Transmission Control Protocol
+    Tcp,
+}
+
+/// UDP datagram or TCP segment payload extracted from a captured frame.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CapturedPayload<'a> {
+    /// Capture time since the UNIX epoch
+    pub timestamp: Duration,
+    /// Transport protocol
+    pub transport: Transport,
+    /// Source address and port
+    pub source: SocketAddr,
+    /// Destination address and port
+    pub destination: SocketAddr,
+    /// Captured bytes of the payload
+    pub data: &'a [u8],
+}
+
+impl<'a> Capture<'a> {
+    /// Read the file header of a pcap or pcapng capture.
+    pub fn new(data: &'a [u8]) -> Result<Self, Error> {
+        Self::parse_header(data).map_err(|e| Error::from_nom(data, e, "capture header"))
+    }
+
+    /// Parse the file header of a pcap or pcapng capture.
+    fn parse_header(data: &'a [u8]) -> Result<Self, Err<nom::error::Error<&'a [u8]>>> {
+        let (_, magic) = take(4usize)(data)?;
+        let (endian, nanoseconds) = match magic {
+            [0x0a, 0x0d, 0x0d, 0x0a] => {
+                let (_, (_, byte_order_magic)) = (take(8usize), take(4usize)).parse(data)?;
+                return Ok(Capture {
+                    records: data,
+                    format: Format::Pcapng {
+                        endian: section_endianness(byte_order_magic)?,
+                        interfaces: Vec::new(),
+                    },
+                });
+            }
+            [0xa1, 0xb2, 0xc3, 0xd4] => (Endianness::Big, false),
+            [0xd4, 0xc3, 0xb2, 0xa1] => (Endianness::Little, false),
+            [0xa1, 0xb2, 0x3c, 0x4d] => (Endianness::Big, true),
+            [0x4d, 0x3c, 0xb2, 0xa1] => (Endianness::Little, true),
+            _ => return Err(Err::Error(nom::error::Error::new(data, ErrorKind::Tag))),
+        };
+
+        // Version, time zone, timestamp accuracy and snapshot length are not needed. The upper
+        // bits of the link type hold the length of frame check sequences.
+        let (records, (_, _, link_type)) =
+            (take(4usize), take(16usize), u32(endian)).parse(data)?;
+        Ok(Capture {
+            records,
+            format: Format::Pcap {
+                endian,
+                nanoseconds,
+                link_type: link_type as u16,
+            },
+        })
+    }
+
+    /// Iterate over the captured frames.
+    pub fn frames(&self) -> CaptureFrames<'a> {
+        CaptureFrames {
+            records: self.records,
+            format: self.format.clone(),
+        }
+    }
+
+    /// Iterate over the UDP and TCP payloads of IPv4 and IPv6 frames, skipping other frames.
+    ///
+    /// Fragmented IP datagrams are skipped. TCP payloads are not reassembled; feed them to a
+    /// [`VrtStreamDecoder`] to decode the VRT packets of a TCP stream.
+    pub fn payloads(&self) -> CapturePayloads<'a> {
+        CapturePayloads {
+            frames: self.frames(),
+            port: None,
+        }
+    }
+
+    /// Iterate over the VRT packets carried by UDP payloads, with their capture timestamps.
+    pub fn vrt_packets(&self) -> CaptureVrtPackets<'a> {
+        CaptureVrtPackets {
+            payloads: self.payloads(),
+            timestamp: Duration::ZERO,
+            remaining: &[],
+        }
+    }
+}
+
+/// Byte order of a pcapng section given its byte-order magic.
+fn section_endianness(magic: &[u8]) -> Result<Endianness, Err<nom::error::Error<&[u8]>>> {
+    match magic {
+        [0x1a, 0x2b, 0x3c, 0x4d] => Ok(Endianness::Big),
+        [0x4d, 0x3c, 0x2b, 0x1a] => Ok(Endianness::Little),
+        _ => Err(Err::Error(nom::error::Error::new(magic, ErrorKind::Tag))),
+    }
+}
+
+/// Iterator over the frames of a [`Capture`].
+///
+/// Iteration stops after the first record that fails to parse.
+#[derive(Clone, Debug)]
+pub struct CaptureFrames<'a> {
+    records: &'a [u8],
+    format: Format,
+}
+
+impl<'a> CaptureFrames<'a> {
+    /// Parse the next pcap record.
+    fn pcap_record(
+        i: &'a [u8],
+        endian: Endianness,
+        nanoseconds: bool,
+        link_type: u16,
+    ) -> IResult<&'a [u8], CapturedFrame<'a>> {
+        let (i, (seconds, fraction, captured_len, original_len)) =
+            (u32(endian), u32(endian), u32(endian), u32(endian)).parse(i)?;
+        let (i, data) = take(captured_len)(i)?;
+        let nanoseconds = if nanoseconds {
+            fraction
+        } else {
+            fraction.saturating_mul(1000)
+        };
+
+        Ok((
+            i,
+            CapturedFrame {
+                timestamp: Duration::from_secs(seconds as u64)
+                    + Duration::from_nanos(nanoseconds as u64),
+                link_type,
+                data,
+                original_len,
+            },
+        ))
+    }
+
+    /// Parse the next pcapng block, returning the frame of an Enhanced Packet Block.
+    ///
+    /// A Section Header Block starts a new section with its own byte order and interfaces.
+    fn pcapng_block(
+        block: &'a [u8],
+        endian: &mut Endianness,
+        interfaces: &mut Vec<Interface>,
+    ) -> IResult<&'a [u8], Option<CapturedFrame<'a>>> {
+        let (_, block_type) = be_u32(block)?;
+        if block_type == BLOCK_SECTION_HEADER {
+            let (_, (_, byte_order_magic)) = (take(8usize), take(4usize)).parse(block)?;
+            *endian = section_endianness(byte_order_magic)?;
+            interfaces.clear();
+        }
+
+        let (i, (block_type, total_len)) = (u32(*endian), u32(*endian)).parse(block)?;
+        if total_len < 12 || total_len % 4 != 0 {
+            return Err(Err::Error(nom::error::Error::new(block, ErrorKind::Verify)));
+        }
+        let (i, body) = take(total_len - 12)(i)?;
+        let (i, _total_len) = u32(*endian)(i)?;
+
+        match block_type {
+            BLOCK_INTERFACE_DESCRIPTION => {
+                interfaces.push(Self::interface(body, *endian)?.1);
+                Ok((i, None))
+            }
+            BLOCK_ENHANCED_PACKET => {
+                let (body, (interface_id, high, low, captured_len, original_len)) = (
+                    u32(*endian),
+                    u32(*endian),
+                    u32(*endian),
+                    u32(*endian),
+                    u32(*endian),
+                )
+                    .parse(body)?;
+                let (_, data) = take(captured_len)(body)?;
+                let interface = interfaces
+                    .get(interface_id as usize)
+                    .ok_or(Err::Error(nom::error::Error::new(block, ErrorKind::Verify)))?;
+                let timestamp = interface
+                    .timestamp(((high as u64) << 32) | low as u64)
+                    .ok_or(Err::Error(nom::error::Error::new(block, ErrorKind::Verify)))?;
+
+                Ok((
+                    i,
+                    Some(CapturedFrame {
+                        timestamp,
+                        link_type: interface.link_type,
+                        data,
+                        original_len,
+                    }),
+                ))
+            }
+            _ => Ok((i, None)),
+        }
+    }
+
+    /// Parse the body of an Interface Description Block.
+    fn interface(i: &[u8], endian: Endianness) -> IResult<&[u8], Interface> {
+        let (mut i, (link_type, _reserved, _snap_len)) =
+            (u16(endian), u16(endian), u32(endian)).parse(i)?;
+        let mut interface = Interface {
+            link_type,
+            units_per_second: 1_000_000,
+            offset: 0,
+        };
+
+        while !i.is_empty() {
+            let (rest, (code, len)) = (u16(endian), u16(endian)).parse(i)?;
+            let (rest, value) = take(len)(rest)?;
+            let (rest, _padding) = take((4 - len as usize % 4) % 4)(rest)?;
+            match code {
+                OPTION_END => break,
+                OPTION_IF_TSRESOL if len == 1 => {
+                    let resolution = value[0];
+                    interface.units_per_second = if resolution & 0x80 == 0 {
+                        10u128.checked_pow(resolution as u32)
+                    } else {
+                        1u128.checked_shl((resolution & 0x7f) as u32)
+                    }
+                    .ok_or(Err::Error(nom::error::Error::new(i, ErrorKind::Verify)))?;
+                }
+                OPTION_IF_TSOFFSET if len == 8 => {
+                    interface.offset = u64(endian)(value)?.1 as i64;
+                }
+                _ => {}
+            }
+            i = rest;
+        }
+
+        Ok((i, interface))
+    }
+}
+
+impl Interface {
+    /// Capture time of a packet timestamp, or `None` if it overflows.
+    fn timestamp(&self, timestamp: u64) -> Option<Duration> {
+        let units = self.units_per_second;
+        let seconds = u64::try_from(timestamp as u128 / units).ok()?;
+        let nanoseconds = (timestamp as u128 % units) * 1_000_000_000 / units;
+        Some(Duration::new(
+            seconds.checked_add_signed(self.offset)?,
+            nanoseconds as u32,
+        ))
+    }
+}
+
+impl<'a> Iterator for CaptureFrames<'a> {
+    type Item = Result<CapturedFrame<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.records.is_empty() {
+            let result = match &mut self.format {
+                Format::Pcap {
+                    endian,
+                    nanoseconds,
+                    link_type,
+                } => Self::pcap_record(self.records, *endian, *nanoseconds, *link_type)
+                    .map(|(i, frame)| (i, Some(frame)))
+                    .map_err(|e| Error::from_nom(self.records, e, "pcap record")),
+                Format::Pcapng { endian, interfaces } => {
+                    Self::pcapng_block(self.records, endian, interfaces)
+                        .map_err(|e| Error::from_nom(self.records, e, "pcapng block"))
+                }
+            };
+            match result {
+                Ok((i, frame)) => {
+                    self.records = i;
+                    if frame.is_some() {
+                        return frame.map(Ok);
+                    }
+                }
+                Err(e) => {
+                    self.records = &[];
+                    return Some(Err(e));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the UDP and TCP payloads of a [`Capture`].
+///
+/// Iteration stops after the first record that fails to parse.
+#[derive(Clone, Debug)]
+pub struct CapturePayloads<'a> {
+    frames: CaptureFrames<'a>,
+    port: Option<u16>,
+}
+
+impl CapturePayloads<'_> {
+    /// Only yield payloads whose source or destination port is `port`.
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+}
+
+impl<'a> Iterator for CapturePayloads<'a> {
+    type Item = Result<CapturedPayload<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let frame = match self.frames.next()? {
+                Ok(frame) => frame,
+                Err(e) => return Some(Err(e)),
+            };
+            let Some(payload) = CapturedPayload::decode(&frame) else {
+                continue;
+            };
+            let port_matches = self.port.map_or(true, |port| {
+                payload.source.port() == port || payload.destination.port() == port
+            });
+            if port_matches && !payload.data.is_empty() {
+                return Some(Ok(payload));
+            }
+        }
+    }
+}
+
+impl<'a> CapturedPayload<'a> {
+    /// Extract the UDP or TCP payload of `frame`, if it carries one.
+    pub fn decode(frame: &CapturedFrame<'a>) -> Option<Self> {
+        let (packet, ethertype) = link_layer(frame.link_type, frame.data).ok()?;
+        let (segment, (protocol, source, destination)) = match ethertype {
+            ETHERTYPE_IPV4 => ipv4(packet),
+            ETHERTYPE_IPV6 => ipv6(packet),
+            _ => return None,
+        }
+        .ok()?;
+        let (data, (transport, source_port, destination_port)) = match protocol {
+            IP_PROTOCOL_UDP => udp(segment),
+            IP_PROTOCOL_TCP => tcp(segment),
+            _ => return None,
+        }
+        .ok()?;
+
+        Some(CapturedPayload {
+            timestamp: frame.timestamp,
+            transport,
+            source: SocketAddr::new(source, source_port),
+            destination: SocketAddr::new(destination, destination_port),
+            data,
+        })
+    }
+}
+
+/// Parse a link-layer header, returning the EtherType of the network-layer packet.
+fn link_layer(link_type: u16, i: &[u8]) -> IResult<&[u8], u16> {
+    match link_type {
+        LINKTYPE_NULL => {
+            // The address family is in the byte order of the capturing host.
+            let (i, family) = u32(Endianness::Little)(i)?;
+            let family = if family > 0xffff {
+                family.swap_bytes()
+            } else {
+                family
+            };
+            match family {
+                2 => Ok((i, ETHERTYPE_IPV4)),
+                24 | 28 | 30 => Ok((i, ETHERTYPE_IPV6)),
+                _ => Ok((i, 0)),
+            }
+        }
+        LINKTYPE_ETHERNET => {
+            let (mut i, (_addresses, mut ethertype)) = (take(12usize), be_u16).parse(i)?;
+            while ethertype == ETHERTYPE_VLAN || ethertype == ETHERTYPE_QINQ {
+                (i, (_, ethertype)) = (be_u16, be_u16).parse(i)?;
+            }
+            Ok((i, ethertype))
+        }
+        LINKTYPE_RAW | LINKTYPE_IPV4 | LINKTYPE_IPV6 => match be_u8(i)?.1 >> 4 {
+            4 => Ok((i, ETHERTYPE_IPV4)),
+            6 => Ok((i, ETHERTYPE_IPV6)),
+            _ => Ok((i, 0)),
+        },
+        LINKTYPE_LINUX_SLL => {
+            let (i, (_, ethertype)) = (take(14usize), be_u16).parse(i)?;
+            Ok((i, ethertype))
+        }
+        LINKTYPE_LINUX_SLL2 => {
+            let (i, (ethertype, _)) = (be_u16, take(18usize)).parse(i)?;
+            Ok((i, ethertype))
+        }
+        _ => Err(Err::Error(nom::error::Error::new(i, ErrorKind::Switch))),
+    }
+}
+
+/// Parse an IPv4 header, returning the protocol and addresses.
+fn ipv4(i: &[u8]) -> IResult<&[u8], (u8, IpAddr, IpAddr)> {
+    let packet = i;
+    let (_, (version_ihl, _, total_len, _, fragment, _, protocol, _, source, destination)) = (
+        be_u8, be_u8, be_u16, be_u16, be_u16, be_u8, be_u8, be_u16, be_u32, be_u32,
+    )
+        .parse(i)?;
+    let header_len = (version_ihl & 0xf) as usize * 4;
+    // Fragmented datagrams, with More Fragments set or a non-zero offset, are not reassembled.
+    if version_ihl >> 4 != 4 || header_len < 20 || fragment & 0x3fff != 0 {
+        return Err(Err::Error(nom::error::Error::new(
+            packet,
+            ErrorKind::Verify,
+        )));
+    }
+    let (payload, _) = take(header_len)(truncate(packet, total_len as usize))?;
+
+    Ok((
+        payload,
+        (
+            protocol,
+            Ipv4Addr::from(source).into(),
+            Ipv4Addr::from(destination).into(),
+        ),
+    ))
+}
+
+/// Parse an IPv6 header and its extension headers, returning the protocol and addresses.
+fn ipv6(i: &[u8]) -> IResult<&[u8], (u8, IpAddr, IpAddr)> {
+    let (i, (version, payload_len, mut next_header, _, source, destination)) =
+        (be_u32, be_u16, be_u8, be_u8, be_u128, be_u128).parse(i)?;
+    if version >> 28 != 6 {
+        return Err(Err::Error(nom::error::Error::new(i, ErrorKind::Verify)));
+    }
+    let mut payload = truncate(i, payload_len as usize);
+    // Skip the Hop-by-Hop Options, Routing, Authentication and Destination Options headers.
+    while matches!(next_header, 0 | 43 | 51 | 60) {
+        let (_, (header, len)) = (be_u8, be_u8).parse(payload)?;
+        let len = if next_header == 51 {
+            (len as usize + 2) * 4
+        } else {
+            (len as usize + 1) * 8
+        };
+        (payload, _) = take(len)(payload)?;
+        next_header = header;
+    }
+
+    Ok((
+        payload,
+        (
+            next_header,
+            Ipv6Addr::from(source).into(),
+            Ipv6Addr::from(destination).into(),
+        ),
+    ))
+}
+
+/// Parse a UDP header, returning the ports.
+fn udp(i: &[u8]) -> IResult<&[u8], (Transport, u16, u16)> {
+    let (i, (source_port, destination_port, len, _checksum)) =
+        (be_u16, be_u16, be_u16, be_u16).parse(i)?;
+    let payload_len = (len as usize).saturating_sub(8);
+    Ok((
+        truncate(i, payload_len),
+        (Transport::Udp, source_port, destination_port),
+    ))
+}
+
+/// Parse a TCP header, returning the ports.
+fn tcp(i: &[u8]) -> IResult<&[u8], (Transport, u16, u16)> {
+    let segment = i;
+    let (_, (source_port, destination_port, _, _, data_offset)) =
+        (be_u16, be_u16, be_u32, be_u32, be_u8).parse(i)?;
+    let header_len = (data_offset >> 4) as usize * 4;
+    if header_len < 20 {
+        return Err(Err::Error(nom::error::Error::new(
+            segment,
+            ErrorKind::Verify,
+        )));
+    }
+    let (payload, _) = take(header_len)(segment)?;
+    Ok((payload, (Transport::Tcp, source_port, destination_port)))
+}
+
+/// The first `len` bytes of `i`, or all of it if the capture was truncated.
+fn truncate(i: &[u8], len: usize) -> &[u8] {
+    &i[..len.min(i.len())]
+}
+
+/// Iterator over the VRT packets carried by the UDP payloads of a [`Capture`].
+///
+/// A payload that fails to parse yields an error and the rest of it is skipped. Iteration stops
+/// after the first record that fails to parse.
+#[derive(Clone, Debug)]
+pub struct CaptureVrtPackets<'a> {
+    payloads: CapturePayloads<'a>,
+    timestamp: Duration,
+    remaining: &'a [u8],
+}
+
+impl CaptureVrtPackets<'_> {
+    /// Only yield packets sent from or to `port`.
+    pub fn port(mut self, port: u16) -> Self {
+        self.payloads = self.payloads.port(port);
+        self
+    }
+}
+
+impl<'a> Iterator for CaptureVrtPackets<'a> {
+    type Item = Result<(Duration, VrtPacket<'a>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining.is_empty() {
+            match self.payloads.next()? {
+                Ok(payload) if payload.transport == Transport::Udp => {
+                    self.timestamp = payload.timestamp;
+                    self.remaining = payload.data;
+                }
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        match VrtPacket::from_bytes(self.remaining) {
+            Ok((packet, len)) => {
+                self.remaining = &self.remaining[len..];
+                Some(Ok((self.timestamp, packet)))
+            }
+            Err(e) => {
+                self.remaining = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,488 @@
+use nom::{
+    bytes::complete::take,
+    combinator::cond,
+    number::complete::{be_i64, be_u16, be_u32, be_u64, be_u8},
+    IResult, Parser,
+};
+
+use super::*;
+
+/// Context Indicator Field 0 (CIF0) fields of an IF Context packet.
+///
+/// Each field is `Some` when its indicator bit is set in the CIF0 word.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Cif0<'a> {
+    /// Indicates that at least one context field has changed since the previous context packet.
+    pub change_indicator: bool,
+    /// Stream ID of the Reference Point.
+    pub reference_point_id: Option<u32>,
+    /// Bandwidth of the signal, in Hz.
+    pub bandwidth: Option<Frequency>,
+    /// IF Reference Frequency, in Hz.
+    pub if_reference_frequency: Option<Frequency>,
+    /// RF Reference Frequency, in Hz.
+    pub rf_reference_frequency: Option<Frequency>,
+    /// RF Reference Frequency Offset, in Hz.
+    pub rf_reference_frequency_offset: Option<Frequency>,
+    /// IF Band Offset, in Hz.
+    pub if_band_offset: Option<Frequency>,
+    /// Reference Level in dBm.
+    pub reference_level: Option<Gain>,
+    /// Front-end and back-end gain.
+    pub gain: Option<GainStages>,
+    /// Number of data samples in the paired data packet whose amplitudes were beyond the range of the data item format.
+    pub over_range_count: Option<u32>,
+    /// Sample Rate, in Hz.
+    pub sample_rate: Option<Frequency>,
+    /// Timestamp Adjustment in femtoseconds.
+    pub timestamp_adjustment: Option<i64>,
+    /// Integer-seconds time at which the timestamp was last calibrated.
+    pub timestamp_calibration_time: Option<u32>,
+    /// Temperature, in degrees Celsius.
+    pub temperature: Option<Temperature>,
+    /// Manufacturer and model of the device generating the context packet stream.
+    pub device_identifier: Option<DeviceIdentifier>,
+    /// State and Event Indicators.
+    pub state_event_indicators: Option<StateEventIndicators>,
+    /// Data Packet Payload Format of the paired data packet stream.
+    pub data_payload_format: Option<PayloadFormat>,
+    /// Formatted GPS Geolocation.
+    pub formatted_gps: Option<Geolocation>,
+    /// Formatted INS Geolocation.
+    pub formatted_ins: Option<Geolocation>,
+    /// Earth-Centered, Earth-Fixed Ephemeris.
+    pub ecef_ephemeris: Option<Ephemeris>,
+    /// Relative Ephemeris.
+    pub relative_ephemeris: Option<Ephemeris>,
+    /// Stream ID of the context packet stream that the Relative Ephemeris is referenced to.
+    pub ephemeris_reference_id: Option<u32>,
+    /// GPS ASCII sentences.
+    pub gps_ascii: Option<GpsAscii<'a>>,
+    /// Context Association Lists.
+    pub context_association_lists: Option<ContextAssociationLists<'a>>,
+}
+
+impl<'a> Cif0<'a> {
+    /// Parse the fields indicated by the CIF0 word `cif0`.
+    pub fn parse_fields(cif0: u32, i: &'a [u8]) -> IResult<&'a [u8], Cif0<'a>> {
+        let bit = |n: u32| (cif0 >> n) & 0x01 != 0;
+
+        let (i, reference_point_id) = cond(bit(30), be_u32).parse(i)?;
+        let (i, bandwidth) = cond(bit(29), Frequency::parse).parse(i)?;
+        let (i, if_reference_frequency) = cond(bit(28), Frequency::parse).parse(i)?;
+        let (i, rf_reference_frequency) = cond(bit(27), Frequency::parse).parse(i)?;
+        let (i, rf_reference_frequency_offset) = cond(bit(26), Frequency::parse).parse(i)?;
+        let (i, if_band_offset) = cond(bit(25), Frequency::parse).parse(i)?;
+        let (i, reference_level) = cond(bit(24), lower_i16.map(Gain::from_raw)).parse(i)?;
+        let (i, gain) = cond(bit(23), GainStages::parse).parse(i)?;
+        let (i, over_range_count) = cond(bit(22), be_u32).parse(i)?;
+        let (i, sample_rate) = cond(bit(21), Frequency::parse).parse(i)?;
+        let (i, timestamp_adjustment) = cond(bit(20), be_i64).parse(i)?;
+        let (i, timestamp_calibration_time) = cond(bit(19), be_u32).parse(i)?;
+        let (i, temperature) = cond(bit(18), lower_i16.map(Temperature::from_raw)).parse(i)?;
+        let (i, device_identifier) = cond(bit(17), DeviceIdentifier::parse).parse(i)?;
+        let (i, state_event_indicators) = cond(bit(16), StateEventIndicators::parse).parse(i)?;
+        let (i, data_payload_format) = cond(bit(15), PayloadFormat::parse).parse(i)?;
+        let (i, formatted_gps) = cond(bit(14), Geolocation::parse).parse(i)?;
+        let (i, formatted_ins) = cond(bit(13), Geolocation::parse).parse(i)?;
+        let (i, ecef_ephemeris) = cond(bit(12), Ephemeris::parse).parse(i)?;
+        let (i, relative_ephemeris) = cond(bit(11), Ephemeris::parse).parse(i)?;
+        let (i, ephemeris_reference_id) = cond(bit(10), be_u32).parse(i)?;
+        let (i, gps_ascii) = cond(bit(9), GpsAscii::parse).parse(i)?;
+        let (i, context_association_lists) =
+            cond(bit(8), ContextAssociationLists::parse).parse(i)?;
+
+        Ok((
+            i,
+            Cif0 {
+                change_indicator: bit(31),
+                reference_point_id,
+                bandwidth,
+                if_reference_frequency,
+                rf_reference_frequency,
+                rf_reference_frequency_offset,
+                if_band_offset,
+                reference_level,
+                gain,
+                over_range_count,
+                sample_rate,
+                timestamp_adjustment,
+                timestamp_calibration_time,
+                temperature,
+                device_identifier,
+                state_event_indicators,
+                data_payload_format,
+                formatted_gps,
+                formatted_ins,
+                ecef_ephemeris,
+                relative_ephemeris,
+                ephemeris_reference_id,
+                gps_ascii,
+                context_association_lists,
+            },
+        ))
+    }
+}
+
+/// Gain of the front-end (stage 2) and back-end (stage 1) of the device.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct GainStages {
+    /// Stage 1 (back-end) gain, in dB.
+    pub stage1: Gain,
+    /// Stage 2 (front-end) gain, in dB.
+    pub stage2: Gain,
+}
+
+impl GainStages {
+    /// Parse the Gain field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], GainStages> {
+        let (i, stage2) = Gain::parse(i)?;
+        let (i, stage1) = Gain::parse(i)?;
+
+        Ok((i, GainStages { stage1, stage2 }))
+    }
+}
+
+/// Device Identifier
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DeviceIdentifier {
+    /// 24-bit Organizationally Unique Identifier of the device manufacturer.
+    pub oui: Oui,
+    /// Manufacturer-assigned device code identifying the model.
+    pub device_code: u16,
+}
+
+impl DeviceIdentifier {
+    /// Parse the Device Identifier field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], DeviceIdentifier> {
+        let (i, oui) = oui(i)?;
+        let (i, _reserved) = be_u16(i)?;
+        let (i, device_code) = be_u16(i)?;
+
+        Ok((i, DeviceIdentifier { oui, device_code }))
+    }
+}
+
+/// State and Event Indicators
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StateEventIndicators {
+    /// Indicates whether or not the calibrated_time_indicator is valid.
+    pub calibrated_time_enable: bool,
+    /// Indicates whether or not the valid_data_indicator is valid.
+    pub valid_data_enable: bool,
+    /// Indicates whether or not the reference_lock_indicator is valid.
+    pub reference_lock_enable: bool,
+    /// Indicates whether or not the agcmgc_indicator is valid.
+    pub agcmgc_enable: bool,
+    /// Indicates whether or not the detected_signal_indicator is valid.
+    pub detected_signal_enable: bool,
+    /// Indicates whether or not the spectral_inversion_indicator is valid.
+    pub spectral_inversion_enable: bool,
+    /// Indicates whether or not the overrange_indicator is valid.
+    pub overrange_enable: bool,
+    /// Indicates whether or not the sample_loss_indicator is valid.
+    pub sample_loss_enable: bool,
+    /// The timestamp is calibrated to an external reference.
+    pub calibrated_time_indicator: bool,
+    /// The data in the paired data packets is valid.
+    pub valid_data_indicator: bool,
+    /// Any phase-locked loops affecting the data are locked and stable.
+    pub reference_lock_indicator: bool,
+    /// AGC is active when true, MGC is active when false.
+    pub agcmgc_indicator: bool,
+    /// The data contains some detected signal.
+    pub detected_signal_indicator: bool,
+    /// The signal has an inverted spectrum referenced to the spectrum at the Reference Point.
+    pub spectral_inversion_indicator: bool,
+    /// At least one data sample exceeded the range of the data item.
+    pub overrange_indicator: bool,
+    /// The paired data contains at least one sample discontinuity.
+    pub sample_loss_indicator: bool,
+    /// User-defined state and event bits.
+    pub user_defined: u8,
+}
+
+impl StateEventIndicators {
+    /// Parse the State and Event Indicators field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], StateEventIndicators> {
+        let (i, first_byte) = be_u8(i)?;
+        let (i, second_byte) = be_u8(i)?;
+        let (i, third_byte) = be_u8(i)?;
+        let (i, user_defined) = be_u8(i)?;
+
+        Ok((
+            i,
+            StateEventIndicators {
+                calibrated_time_enable: (first_byte >> 7) & 0x01 != 0,
+                valid_data_enable: (first_byte >> 6) & 0x01 != 0,
+                reference_lock_enable: (first_byte >> 5) & 0x01 != 0,
+                agcmgc_enable: (first_byte >> 4) & 0x01 != 0,
+                detected_signal_enable: (first_byte >> 3) & 0x01 != 0,
+                spectral_inversion_enable: (first_byte >> 2) & 0x01 != 0,
+                overrange_enable: (first_byte >> 1) & 0x01 != 0,
+                sample_loss_enable: first_byte & 0x01 != 0,
+                calibrated_time_indicator: (second_byte >> 3) & 0x01 != 0,
+                valid_data_indicator: (second_byte >> 2) & 0x01 != 0,
+                reference_lock_indicator: (second_byte >> 1) & 0x01 != 0,
+                agcmgc_indicator: second_byte & 0x01 != 0,
+                detected_signal_indicator: (third_byte >> 7) & 0x01 != 0,
+                spectral_inversion_indicator: (third_byte >> 6) & 0x01 != 0,
+                overrange_indicator: (third_byte >> 5) & 0x01 != 0,
+                sample_loss_indicator: (third_byte >> 4) & 0x01 != 0,
+                user_defined,
+            },
+        ))
+    }
+}
+
+/// Formatted GPS or INS Geolocation
+///
+/// Fields that are not specified by the device hold `0x7FFFFFFF`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Geolocation {
+    /// Type of Integer-seconds Timestamp of the position fix.
+    pub tsi: Tsi,
+    /// Type of Fractional-seconds Timestamp of the position fix.
+    pub tsf: Tsf,
+    /// 24-bit Organizationally Unique Identifier of the GPS/INS manufacturer.
+    pub manufacturer_oui: Oui,
+    /// Integer-seconds timestamp of the position fix.
+    pub integer_timestamp: u32,
+    /// Fractional-seconds timestamp of the position fix.
+    pub fractional_timestamp: u64,
+    /// Latitude, in degrees.
+    pub latitude: Angle,
+    /// Longitude, in degrees.
+    pub longitude: Angle,
+    /// Altitude, in meters.
+    pub altitude: Distance,
+    /// Speed over ground, in meters per second.
+    pub speed_over_ground: Speed,
+    /// Heading angle, in degrees.
+    pub heading_angle: Angle,
+    /// Track angle, in degrees.
+    pub track_angle: Angle,
+    /// Magnetic variation, in degrees.
+    pub magnetic_variation: Angle,
+}
+
+impl Geolocation {
+    /// Parse a Formatted GPS or INS Geolocation field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], Geolocation> {
+        let (i, (tsi, tsf, manufacturer_oui)) = timestamp_types(i)?;
+        let (i, integer_timestamp) = be_u32(i)?;
+        let (i, fractional_timestamp) = be_u64(i)?;
+        let (i, latitude) = Angle::parse(i)?;
+        let (i, longitude) = Angle::parse(i)?;
+        let (i, altitude) = Distance::parse(i)?;
+        let (i, speed_over_ground) = Speed::parse(i)?;
+        let (i, heading_angle) = Angle::parse(i)?;
+        let (i, track_angle) = Angle::parse(i)?;
+        let (i, magnetic_variation) = Angle::parse(i)?;
+
+        Ok((
+            i,
+            Geolocation {
+                tsi,
+                tsf,
+                manufacturer_oui,
+                integer_timestamp,
+                fractional_timestamp,
+                latitude,
+                longitude,
+                altitude,
+                speed_over_ground,
+                heading_angle,
+                track_angle,
+                magnetic_variation,
+            },
+        ))
+    }
+}
+
+/// ECEF or Relative Ephemeris
+///
+/// Fields that are not specified by the device hold `0x7FFFFFFF`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Ephemeris {
+    /// Type of Integer-seconds Timestamp of the ephemeris.
+    pub tsi: Tsi,
+    /// Type of Fractional-seconds Timestamp of the ephemeris.
+    pub tsf: Tsf,
+    /// 24-bit Organizationally Unique Identifier of the ephemeris source manufacturer.
+    pub manufacturer_oui: Oui,
+    /// Integer-seconds timestamp of the ephemeris.
+    pub integer_timestamp: u32,
+    /// Fractional-seconds timestamp of the ephemeris.
+    pub fractional_timestamp: u64,
+    /// Position X, in meters.
+    pub position_x: Distance,
+    /// Position Y, in meters.
+    pub position_y: Distance,
+    /// Position Z, in meters.
+    pub position_z: Distance,
+    /// Attitude alpha, in degrees.
+    pub attitude_alpha: Angle,
+    /// Attitude beta, in degrees.
+    pub attitude_beta: Angle,
+    /// Attitude phi, in degrees.
+    pub attitude_phi: Angle,
+    /// Velocity dX, in meters per second.
+    pub velocity_dx: Speed,
+    /// Velocity dY, in meters per second.
+    pub velocity_dy: Speed,
+    /// Velocity dZ, in meters per second.
+    pub velocity_dz: Speed,
+}
+
+impl Ephemeris {
+    /// Parse an ECEF or Relative Ephemeris field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], Ephemeris> {
+        let (i, (tsi, tsf, manufacturer_oui)) = timestamp_types(i)?;
+        let (i, integer_timestamp) = be_u32(i)?;
+        let (i, fractional_timestamp) = be_u64(i)?;
+        let (i, position_x) = Distance::parse(i)?;
+        let (i, position_y) = Distance::parse(i)?;
+        let (i, position_z) = Distance::parse(i)?;
+        let (i, attitude_alpha) = Angle::parse(i)?;
+        let (i, attitude_beta) = Angle::parse(i)?;
+        let (i, attitude_phi) = Angle::parse(i)?;
+        let (i, velocity_dx) = Speed::parse(i)?;
+        let (i, velocity_dy) = Speed::parse(i)?;
+        let (i, velocity_dz) = Speed::parse(i)?;
+
+        Ok((
+            i,
+            Ephemeris {
+                tsi,
+                tsf,
+                manufacturer_oui,
+                integer_timestamp,
+                fractional_timestamp,
+                position_x,
+                position_y,
+                position_z,
+                attitude_alpha,
+                attitude_beta,
+                attitude_phi,
+                velocity_dx,
+                velocity_dy,
+                velocity_dz,
+            },
+        ))
+    }
+}
+
+/// Parse the first word of a geolocation or ephemeris field: TSI, TSF and manufacturer OUI.
+fn timestamp_types(i: &[u8]) -> IResult<&[u8], (Tsi, Tsf, Oui)> {
+    let (i, word) = be_u32(i)?;
+    // Both are two-bit fields, so the conversions cannot fail.
+    let tsi = Tsi::try_from(((word >> 26) & 0b11) as u8).unwrap_or(Tsi::None);
+    let tsf = Tsf::try_from(((word >> 24) & 0b11) as u8).unwrap_or(Tsf::None);
+
+    Ok((i, (tsi, tsf, Oui::from_word(word))))
+}
+
+/// GPS ASCII
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GpsAscii<'a> {
+    /// 24-bit Organizationally Unique Identifier of the GPS receiver manufacturer.
+    pub manufacturer_oui: Oui,
+    /// ASCII sentences, padded with NUL characters to a multiple of 32-bit words.
+    pub data: &'a [u8],
+}
+
+impl<'a> GpsAscii<'a> {
+    /// Parse the GPS ASCII field
+    pub fn parse(i: &'a [u8]) -> IResult<&'a [u8], GpsAscii<'a>> {
+        let (i, manufacturer_oui) = oui(i)?;
+        let (i, words) = be_u32(i)?;
+        let (i, data) = take((words as usize).saturating_mul(4))(i)?;
+
+        Ok((
+            i,
+            GpsAscii {
+                manufacturer_oui,
+                data,
+            },
+        ))
+    }
+}
+
+/// Context Association Lists
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ContextAssociationLists<'a> {
+    /// Stream IDs of the source context streams.
+    pub source: StreamIdList<'a>,
+    /// Stream IDs of the system context streams.
+    pub system: StreamIdList<'a>,
+    /// Stream IDs of the vector-component context streams.
+    pub vector_component: StreamIdList<'a>,
+    /// Stream IDs of the asynchronous-channel context streams.
+    pub asynchronous_channel: StreamIdList<'a>,
+    /// Tags of the asynchronous channels, one per asynchronous-channel stream.
+    pub asynchronous_channel_tags: Option<StreamIdList<'a>>,
+}
+
+impl<'a> ContextAssociationLists<'a> {
+    /// Parse the Context Association Lists field
+    pub fn parse(i: &'a [u8]) -> IResult<&'a [u8], ContextAssociationLists<'a>> {
+        let (i, first_word) = be_u32(i)?;
+        let (i, second_word) = be_u32(i)?;
+        let source_size = (first_word >> 16) & 0x1ff;
+        let system_size = first_word & 0x1ff;
+        let vector_component_size = second_word >> 16;
+        let tags_enable = (second_word >> 15) & 0x01 != 0;
+        let asynchronous_channel_size = second_word & 0x7fff;
+
+        let (i, source) = StreamIdList::parse(source_size, i)?;
+        let (i, system) = StreamIdList::parse(system_size, i)?;
+        let (i, vector_component) = StreamIdList::parse(vector_component_size, i)?;
+        let (i, asynchronous_channel) = StreamIdList::parse(asynchronous_channel_size, i)?;
+        let (i, asynchronous_channel_tags) = cond(tags_enable, |i| {
+            StreamIdList::parse(asynchronous_channel_size, i)
+        })
+        .parse(i)?;
+
+        Ok((
+            i,
+            ContextAssociationLists {
+                source,
+                system,
+                vector_component,
+                asynchronous_channel,
+                asynchronous_channel_tags,
+            },
+        ))
+    }
+}
+
+/// List of 32-bit Stream Identifiers borrowed from the packet.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StreamIdList<'a>(&'a [u8]);
+
+impl<'a> StreamIdList<'a> {
+    /// Parse a list of `len` Stream Identifiers
+    pub fn parse(len: u32, i: &'a [u8]) -> IResult<&'a [u8], StreamIdList<'a>> {
+        let (i, list) = take((len as usize).saturating_mul(4))(i)?;
+
+        Ok((i, StreamIdList(list)))
+    }
+
+    /// Number of Stream Identifiers in the list.
+    pub fn len(&self) -> usize {
+        self.0.len() / size_of::<u32>()
+    }
+
+    /// Is the list empty?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the Stream Identifiers in the list.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + 'a {
+        self.0
+            .chunks_exact(size_of::<u32>())
+            .map(|chunk| u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+    }
+}
@@ -0,0 +1,364 @@
+use nom::{
+    bytes::complete::take,
+    combinator::{cond, peek},
+    number::complete::{be_i32, be_u32, be_u64},
+    IResult, Parser,
+};
+
+use super::*;
+
+/// Context Indicator Field 1 (CIF1) fields of a VITA 49.2 context packet.
+///
+/// Each field is `Some` when its indicator bit is set in the CIF1 word.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Cif1<'a> {
+    /// Phase Offset, in radians.
+    pub phase_offset: Option<Phase>,
+    /// Polarization
+    pub polarization: Option<Polarization>,
+    /// Single 3-D Pointing Vector
+    pub pointing_vector: Option<PointingVector>,
+    /// 3-D Pointing Vector Structure
+    pub pointing_vector_structure: Option<ArrayOfRecords<'a>>,
+    /// Spatial Scan Type
+    pub spatial_scan_type: Option<u16>,
+    /// Spatial Reference Type
+    pub spatial_reference_type: Option<u32>,
+    /// Beam Widths
+    pub beam_widths: Option<BeamWidths>,
+    /// Range (distance), in meters.
+    pub range: Option<RangeDistance>,
+    /// Energy per bit to noise density ratio and bit error rate.
+    pub eb_no_ber: Option<EbNoBer>,
+    /// Threshold, encoded like the Gain field.
+    pub threshold: Option<GainStages>,
+    /// Compression Point in dBm.
+    pub compression_point: Option<Gain>,
+    /// Second- and third-order Intercept Points.
+    pub intercept_points: Option<InterceptPoints>,
+    /// Signal-to-Noise Ratio and Noise Figure.
+    pub snr_noise_figure: Option<SnrNoiseFigure>,
+    /// Aux Frequency, in Hz.
+    pub aux_frequency: Option<Frequency>,
+    /// Aux Gain, encoded like the Gain field.
+    pub aux_gain: Option<GainStages>,
+    /// Aux Bandwidth, in Hz.
+    pub aux_bandwidth: Option<Frequency>,
+    /// Array of CIFs
+    pub array_of_cifs: Option<ArrayOfRecords<'a>>,
+    /// Spectrum
+    pub spectrum: Option<Spectrum>,
+    /// Sector Scan/Step
+    pub sector_scan_step: Option<ArrayOfRecords<'a>>,
+    /// Index List
+    pub index_list: Option<ArrayOfRecords<'a>>,
+    /// 32-bit Discrete I/O
+    pub discrete_io32: Option<u32>,
+    /// 64-bit Discrete I/O
+    pub discrete_io64: Option<u64>,
+    /// Health Status
+    pub health_status: Option<u16>,
+    /// V49 Spec Compliance
+    pub v49_spec_compliance: Option<u32>,
+    /// Version and Build Code
+    pub version_build_code: Option<u32>,
+    /// Buffer Size, level and status.
+    pub buffer_size: Option<u64>,
+}
+
+impl<'a> Cif1<'a> {
+    /// Parse the fields indicated by the CIF1 word `cif1`.
+    pub fn parse_fields(cif1: u32, i: &'a [u8]) -> IResult<&'a [u8], Cif1<'a>> {
+        let bit = |n: u32| (cif1 >> n) & 0x01 != 0;
+
+        let (i, phase_offset) = cond(bit(31), lower_i16.map(Phase::from_raw)).parse(i)?;
+        let (i, polarization) = cond(bit(30), Polarization::parse).parse(i)?;
+        let (i, pointing_vector) = cond(bit(29), PointingVector::parse).parse(i)?;
+        let (i, pointing_vector_structure) = cond(bit(28), ArrayOfRecords::parse).parse(i)?;
+        let (i, spatial_scan_type) = cond(bit(27), lower_u16).parse(i)?;
+        let (i, spatial_reference_type) = cond(bit(26), be_u32).parse(i)?;
+        let (i, beam_widths) = cond(bit(25), BeamWidths::parse).parse(i)?;
+        let (i, range) = cond(bit(24), RangeDistance::parse).parse(i)?;
+        let (i, eb_no_ber) = cond(bit(20), EbNoBer::parse).parse(i)?;
+        let (i, threshold) = cond(bit(19), GainStages::parse).parse(i)?;
+        let (i, compression_point) = cond(bit(18), lower_i16.map(Gain::from_raw)).parse(i)?;
+        let (i, intercept_points) = cond(bit(17), InterceptPoints::parse).parse(i)?;
+        let (i, snr_noise_figure) = cond(bit(16), SnrNoiseFigure::parse).parse(i)?;
+        let (i, aux_frequency) = cond(bit(15), Frequency::parse).parse(i)?;
+        let (i, aux_gain) = cond(bit(14), GainStages::parse).parse(i)?;
+        let (i, aux_bandwidth) = cond(bit(13), Frequency::parse).parse(i)?;
+        let (i, array_of_cifs) = cond(bit(11), ArrayOfRecords::parse).parse(i)?;
+        let (i, spectrum) = cond(bit(10), Spectrum::parse).parse(i)?;
+        let (i, sector_scan_step) = cond(bit(9), ArrayOfRecords::parse).parse(i)?;
+        let (i, index_list) = cond(bit(7), ArrayOfRecords::parse).parse(i)?;
+        let (i, discrete_io32) = cond(bit(6), be_u32).parse(i)?;
+        let (i, discrete_io64) = cond(bit(5), be_u64).parse(i)?;
+        let (i, health_status) = cond(bit(4), lower_u16).parse(i)?;
+        let (i, v49_spec_compliance) = cond(bit(3), be_u32).parse(i)?;
+        let (i, version_build_code) = cond(bit(2), be_u32).parse(i)?;
+        let (i, buffer_size) = cond(bit(1), be_u64).parse(i)?;
+
+        Ok((
+            i,
+            Cif1 {
+                phase_offset,
+                polarization,
+                pointing_vector,
+                pointing_vector_structure,
+                spatial_scan_type,
+                spatial_reference_type,
+                beam_widths,
+                range,
+                eb_no_ber,
+                threshold,
+                compression_point,
+                intercept_points,
+                snr_noise_figure,
+                aux_frequency,
+                aux_gain,
+                aux_bandwidth,
+                array_of_cifs,
+                spectrum,
+                sector_scan_step,
+                index_list,
+                discrete_io32,
+                discrete_io64,
+                health_status,
+                v49_spec_compliance,
+                version_build_code,
+                buffer_size,
+            },
+        ))
+    }
+}
+
+/// Polarization
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Polarization {
+    /// Tilt angle, in radians.
+    pub tilt_angle: PolarizationAngle,
+    /// Ellipticity angle, in radians.
+    pub ellipticity_angle: PolarizationAngle,
+}
+
+impl Polarization {
+    /// Parse the Polarization field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], Polarization> {
+        let (i, tilt_angle) = PolarizationAngle::parse(i)?;
+        let (i, ellipticity_angle) = PolarizationAngle::parse(i)?;
+
+        Ok((
+            i,
+            Polarization {
+                tilt_angle,
+                ellipticity_angle,
+            },
+        ))
+    }
+}
+
+/// Single 3-D Pointing Vector
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PointingVector {
+    /// Elevation angle, in degrees.
+    pub elevation: PointingAngle,
+    /// Azimuthal angle, in degrees.
+    pub azimuth: Azimuth,
+}
+
+impl PointingVector {
+    /// Parse the 3-D Pointing Vector field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], PointingVector> {
+        let (i, elevation) = PointingAngle::parse(i)?;
+        let (i, azimuth) = Azimuth::parse(i)?;
+
+        Ok((i, PointingVector { elevation, azimuth }))
+    }
+}
+
+/// Beam Widths
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BeamWidths {
+    /// Horizontal beam width, in degrees.
+    pub horizontal: PointingAngle,
+    /// Vertical beam width, in degrees.
+    pub vertical: PointingAngle,
+}
+
+impl BeamWidths {
+    /// Parse the Beam Widths field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], BeamWidths> {
+        let (i, horizontal) = PointingAngle::parse(i)?;
+        let (i, vertical) = PointingAngle::parse(i)?;
+
+        Ok((
+            i,
+            BeamWidths {
+                horizontal,
+                vertical,
+            },
+        ))
+    }
+}
+
+/// Eb/No and Bit Error Rate
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct EbNoBer {
+    /// Eb/No in dB.
+    pub eb_no: Gain,
+    /// Bit error rate.
+    pub bit_error_rate: BitErrorRate,
+}
+
+impl EbNoBer {
+    /// Parse the Eb/No BER field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], EbNoBer> {
+        let (i, eb_no) = Gain::parse(i)?;
+        let (i, bit_error_rate) = BitErrorRate::parse(i)?;
+
+        Ok((
+            i,
+            EbNoBer {
+                eb_no,
+                bit_error_rate,
+            },
+        ))
+    }
+}
+
+/// Second- and third-order Intercept Points
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct InterceptPoints {
+    /// Second-order intercept point in dBm.
+    pub second_order: Gain,
+    /// Third-order intercept point in dBm.
+    pub third_order: Gain,
+}
+
+impl InterceptPoints {
+    /// Parse the Intercept Points field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], InterceptPoints> {
+        let (i, second_order) = Gain::parse(i)?;
+        let (i, third_order) = Gain::parse(i)?;
+
+        Ok((
+            i,
+            InterceptPoints {
+                second_order,
+                third_order,
+            },
+        ))
+    }
+}
+
+/// Signal-to-Noise Ratio and Noise Figure
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SnrNoiseFigure {
+    /// Signal-to-noise ratio in dB.
+    pub snr: Gain,
+    /// Noise figure in dB.
+    pub noise_figure: Gain,
+}
+
+impl SnrNoiseFigure {
+    /// Parse the SNR/Noise Figure field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], SnrNoiseFigure> {
+        let (i, snr) = Gain::parse(i)?;
+        let (i, noise_figure) = Gain::parse(i)?;
+
+        Ok((i, SnrNoiseFigure { snr, noise_figure }))
+    }
+}
+
+/// Spectrum
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Spectrum {
+    /// Spectrum type
+    pub spectrum_type: u32,
+    /// Window type
+    pub window_type: u32,
+    /// Number of transform points
+    pub num_transform_points: u32,
+    /// Number of window points
+    pub num_window_points: u32,
+    /// Resolution, in Hz.
+    pub resolution: Frequency,
+    /// Span, in Hz.
+    pub span: Frequency,
+    /// Number of averages
+    pub num_averages: u32,
+    /// Weighting factor
+    pub weighting_factor: i32,
+    /// Index of the first frequency bin (F1)
+    pub f1_index: i32,
+    /// Index of the last frequency bin (F2)
+    pub f2_index: i32,
+    /// Window time delta
+    pub window_time_delta: u32,
+}
+
+impl Spectrum {
+    /// Parse the Spectrum field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], Spectrum> {
+        let (i, spectrum_type) = be_u32(i)?;
+        let (i, window_type) = be_u32(i)?;
+        let (i, num_transform_points) = be_u32(i)?;
+        let (i, num_window_points) = be_u32(i)?;
+        let (i, resolution) = Frequency::parse(i)?;
+        let (i, span) = Frequency::parse(i)?;
+        let (i, num_averages) = be_u32(i)?;
+        let (i, weighting_factor) = be_i32(i)?;
+        let (i, f1_index) = be_i32(i)?;
+        let (i, f2_index) = be_i32(i)?;
+        let (i, window_time_delta) = be_u32(i)?;
+
+        Ok((
+            i,
+            Spectrum {
+                spectrum_type,
+                window_type,
+                num_transform_points,
+                num_window_points,
+                resolution,
+                span,
+                num_averages,
+                weighting_factor,
+                f1_index,
+                f2_index,
+                window_time_delta,
+            },
+        ))
+    }
+}
+
+/// Variable-length field whose first word holds its total size in 32-bit words.
+///
+/// Used for the Array-of-Records fields (3-D Pointing Vector Structure, Array of CIFs and
+/// Sector Scan/Step) and the Index List. The record layout is left to the caller.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ArrayOfRecords<'a> {
+    /// The words following the size word.
+    pub data: &'a [u8],
+}
+
+impl<'a> ArrayOfRecords<'a> {
+    /// Parse a variable-length field
+    pub fn parse(i: &'a [u8]) -> IResult<&'a [u8], ArrayOfRecords<'a>> {
+        let (_, words) = peek(be_u32).parse(i)?;
+        let (i, field) = take((words.max(1) as usize).saturating_mul(4))(i)?;
+
+        Ok((
+            i,
+            ArrayOfRecords {
+                data: &field[size_of::<u32>()..],
+            },
+        ))
+    }
+
+    /// Iterate over the 32-bit words following the size word.
+    pub fn words(&self) -> impl Iterator<Item = u32> + 'a {
+        self.data
+            .chunks_exact(size_of::<u32>())
+            .map(|chunk| u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+    }
+}
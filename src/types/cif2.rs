@@ -0,0 +1,144 @@
+use nom::{
+    combinator::cond,
+    number::complete::{be_u128, be_u32},
+    IResult, Parser,
+};
+
+/// Context Indicator Field 2 (CIF2) fields of a VITA 49.2 context packet.
+///
+/// CIF2 carries identifiers of the stream, its controller and the emitter it describes.
+///
+/// Each field is `Some` when its indicator bit is set in the CIF2 word.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Cif2 {
+    /// Bind
+    pub bind: Option<u32>,
+    /// Cited SID
+    pub cited_sid: Option<u32>,
+    /// Sibling SID
+    pub sibling_sid: Option<u32>,
+    /// Parent SID
+    pub parent_sid: Option<u32>,
+    /// Child SID
+    pub child_sid: Option<u32>,
+    /// Cited Message ID
+    pub cited_message_id: Option<u32>,
+    /// Controllee ID
+    pub controllee_id: Option<u32>,
+    /// Controllee UUID
+    pub controllee_uuid: Option<u128>,
+    /// Controller ID
+    pub controller_id: Option<u32>,
+    /// Controller UUID
+    pub controller_uuid: Option<u128>,
+    /// Information Source
+    pub information_source: Option<u32>,
+    /// Track ID
+    pub track_id: Option<u32>,
+    /// Country Code
+    pub country_code: Option<u32>,
+    /// Operator
+    pub operator: Option<u32>,
+    /// Platform Class
+    pub platform_class: Option<u32>,
+    /// Platform Instance
+    pub platform_instance: Option<u32>,
+    /// Platform Display
+    pub platform_display: Option<u32>,
+    /// EMS Device Class
+    pub ems_device_class: Option<u32>,
+    /// EMS Device Type
+    pub ems_device_type: Option<u32>,
+    /// EMS Device Instance
+    pub ems_device_instance: Option<u32>,
+    /// Modulation Class
+    pub modulation_class: Option<u32>,
+    /// Modulation Type
+    pub modulation_type: Option<u32>,
+    /// Function ID
+    pub function_id: Option<u32>,
+    /// Mode ID
+    pub mode_id: Option<u32>,
+    /// Event ID
+    pub event_id: Option<u32>,
+    /// Function Priority ID
+    pub function_priority_id: Option<u32>,
+    /// Communication Priority ID
+    pub communication_priority_id: Option<u32>,
+    /// RF Footprint
+    pub rf_footprint: Option<u32>,
+    /// RF Footprint Range
+    pub rf_footprint_range: Option<u32>,
+}
+
+impl Cif2 {
+    /// Parse the fields indicated by the CIF2 word `cif2`.
+    pub fn parse_fields(cif2: u32, i: &[u8]) -> IResult<&[u8], Cif2> {
+        let bit = |n: u32| (cif2 >> n) & 0x01 != 0;
+
+        let (i, bind) = cond(bit(31), be_u32).parse(i)?;
+        let (i, cited_sid) = cond(bit(30), be_u32).parse(i)?;
+        let (i, sibling_sid) = cond(bit(29), be_u32).parse(i)?;
+        let (i, parent_sid) = cond(bit(28), be_u32).parse(i)?;
+        let (i, child_sid) = cond(bit(27), be_u32).parse(i)?;
+        let (i, cited_message_id) = cond(bit(26), be_u32).parse(i)?;
+        let (i, controllee_id) = cond(bit(25), be_u32).parse(i)?;
+        let (i, controllee_uuid) = cond(bit(24), be_u128).parse(i)?;
+        let (i, controller_id) = cond(bit(23), be_u32).parse(i)?;
+        let (i, controller_uuid) = cond(bit(22), be_u128).parse(i)?;
+        let (i, information_source) = cond(bit(21), be_u32).parse(i)?;
+        let (i, track_id) = cond(bit(20), be_u32).parse(i)?;
+        let (i, country_code) = cond(bit(19), be_u32).parse(i)?;
+        let (i, operator) = cond(bit(18), be_u32).parse(i)?;
+        let (i, platform_class) = cond(bit(17), be_u32).parse(i)?;
+        let (i, platform_instance) = cond(bit(16), be_u32).parse(i)?;
+        let (i, platform_display) = cond(bit(15), be_u32).parse(i)?;
+        let (i, ems_device_class) = cond(bit(14), be_u32).parse(i)?;
+        let (i, ems_device_type) = cond(bit(13), be_u32).parse(i)?;
+        let (i, ems_device_instance) = cond(bit(12), be_u32).parse(i)?;
+        let (i, modulation_class) = cond(bit(11), be_u32).parse(i)?;
+        let (i, modulation_type) = cond(bit(10), be_u32).parse(i)?;
+        let (i, function_id) = cond(bit(9), be_u32).parse(i)?;
+        let (i, mode_id) = cond(bit(8), be_u32).parse(i)?;
+        let (i, event_id) = cond(bit(7), be_u32).parse(i)?;
+        let (i, function_priority_id) = cond(bit(6), be_u32).parse(i)?;
+        let (i, communication_priority_id) = cond(bit(5), be_u32).parse(i)?;
+        let (i, rf_footprint) = cond(bit(4), be_u32).parse(i)?;
+        let (i, rf_footprint_range) = cond(bit(3), be_u32).parse(i)?;
+
+        Ok((
+            i,
+            Cif2 {
+                bind,
+                cited_sid,
+                sibling_sid,
+                parent_sid,
+                child_sid,
+                cited_message_id,
+                controllee_id,
+                controllee_uuid,
+                controller_id,
+                controller_uuid,
+                information_source,
+                track_id,
+                country_code,
+                operator,
+                platform_class,
+                platform_instance,
+                platform_display,
+                ems_device_class,
+                ems_device_type,
+                ems_device_instance,
+                modulation_class,
+                modulation_type,
+                function_id,
+                mode_id,
+                event_id,
+                function_priority_id,
+                communication_priority_id,
+                rf_footprint,
+                rf_footprint_range,
+            },
+        ))
+    }
+}
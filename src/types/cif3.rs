@@ -0,0 +1,107 @@
+use nom::{
+    combinator::cond,
+    number::complete::{be_i64, be_u32, be_u64},
+    IResult, Parser,
+};
+
+use super::*;
+
+/// Context Indicator Field 3 (CIF3) fields of a VITA 49.2 context packet.
+///
+/// CIF3 carries temporal and environmental attributes of the signal.
+///
+/// Each field is `Some` when its indicator bit is set in the CIF3 word.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Cif3 {
+    /// Timestamp Details
+    pub timestamp_details: Option<u64>,
+    /// Timestamp Skew in femtoseconds
+    pub timestamp_skew: Option<i64>,
+    /// Rise Time in femtoseconds
+    pub rise_time: Option<u64>,
+    /// Fall Time in femtoseconds
+    pub fall_time: Option<u64>,
+    /// Offset Time in femtoseconds
+    pub offset_time: Option<i64>,
+    /// Pulse Width in femtoseconds
+    pub pulse_width: Option<u64>,
+    /// Period in femtoseconds
+    pub period: Option<u64>,
+    /// Duration in femtoseconds
+    pub duration: Option<u64>,
+    /// Dwell in femtoseconds
+    pub dwell: Option<u64>,
+    /// Jitter in femtoseconds
+    pub jitter: Option<u64>,
+    /// Age in femtoseconds
+    pub age: Option<u64>,
+    /// Shelf Life in femtoseconds
+    pub shelf_life: Option<u64>,
+    /// Air Temperature, in degrees Celsius.
+    pub air_temperature: Option<Temperature>,
+    /// Sea/Ground Temperature, in degrees Celsius.
+    pub sea_ground_temperature: Option<Temperature>,
+    /// Relative Humidity, in percent.
+    pub humidity: Option<Humidity>,
+    /// Barometric Pressure, in hectopascals.
+    pub barometric_pressure: Option<Pressure>,
+    /// Sea and Swell State
+    pub sea_swell_state: Option<u32>,
+    /// Tropospheric State
+    pub tropospheric_state: Option<u32>,
+    /// Network ID
+    pub network_id: Option<u32>,
+}
+
+impl Cif3 {
+    /// Parse the fields indicated by the CIF3 word `cif3`.
+    pub fn parse_fields(cif3: u32, i: &[u8]) -> IResult<&[u8], Cif3> {
+        let bit = |n: u32| (cif3 >> n) & 0x01 != 0;
+
+        let (i, timestamp_details) = cond(bit(31), be_u64).parse(i)?;
+        let (i, timestamp_skew) = cond(bit(30), be_i64).parse(i)?;
+        let (i, rise_time) = cond(bit(27), be_u64).parse(i)?;
+        let (i, fall_time) = cond(bit(26), be_u64).parse(i)?;
+        let (i, offset_time) = cond(bit(25), be_i64).parse(i)?;
+        let (i, pulse_width) = cond(bit(24), be_u64).parse(i)?;
+        let (i, period) = cond(bit(23), be_u64).parse(i)?;
+        let (i, duration) = cond(bit(22), be_u64).parse(i)?;
+        let (i, dwell) = cond(bit(21), be_u64).parse(i)?;
+        let (i, jitter) = cond(bit(20), be_u64).parse(i)?;
+        let (i, age) = cond(bit(17), be_u64).parse(i)?;
+        let (i, shelf_life) = cond(bit(16), be_u64).parse(i)?;
+        let (i, air_temperature) = cond(bit(7), lower_i16.map(Temperature::from_raw)).parse(i)?;
+        let (i, sea_ground_temperature) =
+            cond(bit(6), lower_i16.map(Temperature::from_raw)).parse(i)?;
+        let (i, humidity) = cond(bit(5), lower_u16.map(Humidity::from_raw)).parse(i)?;
+        let (i, barometric_pressure) = cond(bit(4), Pressure::parse).parse(i)?;
+        let (i, sea_swell_state) = cond(bit(3), be_u32).parse(i)?;
+        let (i, tropospheric_state) = cond(bit(2), be_u32).parse(i)?;
+        let (i, network_id) = cond(bit(1), be_u32).parse(i)?;
+
+        Ok((
+            i,
+            Cif3 {
+                timestamp_details,
+                timestamp_skew,
+                rise_time,
+                fall_time,
+                offset_time,
+                pulse_width,
+                period,
+                duration,
+                dwell,
+                jitter,
+                age,
+                shelf_life,
+                air_temperature,
+                sea_ground_temperature,
+                humidity,
+                barometric_pressure,
+                sea_swell_state,
+                tropospheric_state,
+                network_id,
+            },
+        ))
+    }
+}
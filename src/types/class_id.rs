@@ -3,11 +3,49 @@ use nom::{
     IResult,
 };
 
+use super::ensure_capacity;
+use crate::Error;
+
+/// IEEE Organizationally Unique Identifier, a 24-bit value.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Oui(u32);
+
+impl Oui {
+    /// Largest OUI value.
+    pub const MAX: u32 = 0x00ff_ffff;
+
+    /// The OUI `value`, or `None` if it does not fit in 24 bits.
+    pub const fn new(value: u32) -> Option<Self> {
+        if value > Self::MAX {
+            return None;
+        }
+        Some(Oui(value))
+    }
+
+    /// The OUI held in the 24 least-significant bits of `word`.
+    pub(crate) const fn from_word(word: u32) -> Self {
+        Oui(word & Self::MAX)
+    }
+
+    /// The OUI as a right-justified 24-bit value.
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<Oui> for u32 {
+    fn from(oui: Oui) -> u32 {
+        oui.0
+    }
+}
+
 /// Class Identifier
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct ClassId {
+    /// Number of pad bits at the end of the data payload (0-31).
+    pub pad_bit_count: u8,
     /// Organizationally Unique Identifier assigned by IEEE, VITA, the VRT Profile author, or a reserved OUI.
-    pub oui: u32,
+    pub oui: Oui,
     /// Information Class Code
     pub information_class_code: u16,
     /// Packet Class Code
@@ -15,19 +53,35 @@ pub struct ClassId {
 }
 
 impl ClassId {
+    /// Size of the encoded Class ID in bytes.
+    pub const SIZE: usize = 2 * size_of::<u32>();
+
     /// Parse the Class ID
     pub fn parse(i: &[u8]) -> IResult<&[u8], ClassId> {
-        let (i, oui) = be_u32(i)?;
+        let (i, word) = be_u32(i)?;
         let (i, information_class_code) = be_u16(i)?;
         let (i, packet_class_code) = be_u16(i)?;
 
         Ok((
             i,
             ClassId {
-                oui,
+                pad_bit_count: (word >> 27) as u8,
+                oui: Oui::from_word(word),
                 information_class_code,
                 packet_class_code,
             },
         ))
     }
+
+    /// Encode the Class ID into `buf`, returning the number of bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        ensure_capacity(buf, Self::SIZE)?;
+
+        let word = (((self.pad_bit_count & 0x1f) as u32) << 27) | self.oui.get();
+        buf[0..4].copy_from_slice(&word.to_be_bytes());
+        buf[4..6].copy_from_slice(&self.information_class_code.to_be_bytes());
+        buf[6..8].copy_from_slice(&self.packet_class_code.to_be_bytes());
+
+        Ok(Self::SIZE)
+    }
 }
@@ -0,0 +1,445 @@
+use nom::{
+    bytes::complete::take,
+    combinator::{cond, map_res},
+    number::complete::{be_u128, be_u32},
+    Err, IResult, Parser,
+};
+
+use super::*;
+use crate::Error;
+
+/// VITA 49.2 Command Packet
+#[derive(Debug, PartialEq)]
+pub struct CommandPacket<'a> {
+    /// VRT Packet Header
+    pub header: Header,
+    /// Stream Id
+    pub stream_id: u32,
+    /// Optional Class Id
+    pub class_id: Option<ClassId>,
+    /// Optional Integer-Seconds Timestamp
+    pub tsi: Option<u32>,
+    /// Optional Fractional-Seconds Timestamp
+    pub tsf: Option<u64>,
+    /// Control/Acknowledge Mode field
+    pub cam: Cam,
+    /// Message ID
+    pub message_id: u32,
+    /// Optional ControlleE identifier
+    pub controllee_id: Option<ControlId>,
+    /// Optional ControlleR identifier
+    pub controller_id: Option<ControlId>,
+    /// Kind of command and its contents
+    pub kind: CommandKind<'a>,
+}
+
+impl<'a> CommandPacket<'a> {
+    /// Parse the VRT command packet
+    pub fn parse(i: &'a [u8]) -> IResult<&'a [u8], CommandPacket<'a>> {
+        let (i, packet) = VrtPacket::parse(i)?;
+        let packet = CommandPacket::from_packet(packet)?;
+
+        Ok((i, packet))
+    }
+
+    /// Parse the VRT command packet at the start of `bytes`, returning it along with its length
+    /// in bytes.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<(CommandPacket<'a>, usize), Error> {
+        let (packet, len) = VrtPacket::from_bytes(bytes)?;
+        let offset = packet.payload.as_ptr() as usize - bytes.as_ptr() as usize;
+        let packet = CommandPacket::try_from(packet).map_err(|e| e.offset_by(offset))?;
+
+        Ok((packet, len))
+    }
+
+    /// Decode the command fields carried in the payload of a Command packet.
+    fn from_packet(packet: VrtPacket<'a>) -> Result<Self, Err<nom::error::Error<&'a [u8]>>> {
+        let verify = |i| Err::Error(nom::error::Error::new(i, nom::error::ErrorKind::Verify));
+
+        if packet.header.packet_type != PktType::Command {
+            return Err(verify(packet.payload));
+        }
+        let Indicators::Command {
+            acknowledge,
+            cancel,
+        } = packet.header.indicators
+        else {
+            return Err(verify(packet.payload));
+        };
+        let stream_id = packet.stream_id.ok_or_else(|| verify(packet.payload))?;
+
+        let (i, cam) = map_res(be_u32, Cam::try_from).parse(packet.payload)?;
+        let (i, message_id) = be_u32(i)?;
+        let (i, controllee_id) = cond(
+            cam.controllee_enable,
+            ControlId::parser(cam.controllee_uuid),
+        )
+        .parse(i)?;
+        let (i, controller_id) = cond(
+            cam.controller_enable,
+            ControlId::parser(cam.controller_uuid),
+        )
+        .parse(i)?;
+
+        let kind = match (acknowledge, cancel) {
+            (false, false) => CommandKind::Control(ContextFields::parse(i)?.1),
+            (false, true) => CommandKind::Cancel(IndicatorWords::parse(i)?.1),
+            (true, false) if cam.query_state => CommandKind::QueryAck(ContextFields::parse(i)?.1),
+            (true, false) if cam.execution => {
+                CommandKind::ExecutionAck(Acknowledgement::parse(&cam, i)?.1)
+            }
+            (true, false) if cam.validation => {
+                CommandKind::ValidationAck(Acknowledgement::parse(&cam, i)?.1)
+            }
+            _ => return Err(verify(i)),
+        };
+
+        Ok(CommandPacket {
+            header: packet.header,
+            stream_id,
+            class_id: packet.class_id,
+            tsi: packet.tsi,
+            tsf: packet.tsf,
+            cam,
+            message_id,
+            controllee_id,
+            controller_id,
+            kind,
+        })
+    }
+}
+
+impl<'a> TryFrom<VrtPacket<'a>> for CommandPacket<'a> {
+    type Error = Error;
+
+    /// Decode the command fields carried in the payload of a Command packet.
+    ///
+    /// Extension Command packets, whose payload is defined by their packet class, are rejected
+    /// with [`Error::UnsupportedPacketType`]. Invalid fields are reported with their byte offset
+    /// from the start of the payload.
+    fn try_from(packet: VrtPacket<'a>) -> Result<Self, Error> {
+        let packet_type = packet.header.packet_type;
+        if packet_type != PktType::Command {
+            return Err(Error::UnsupportedPacketType(packet_type.into()));
+        }
+        if packet.stream_id.is_none() {
+            return Err(Error::HeaderMismatch("stream_id"));
+        }
+        let payload = packet.payload;
+        CommandPacket::from_packet(packet)
+            .map_err(|e| Error::from_nom(payload, e, "command fields"))
+    }
+}
+
+/// Kind of a command packet and the fields it carries.
+#[derive(Debug, PartialEq)]
+pub enum CommandKind<'a> {
+    /// Control packet carrying the context fields to apply.
+    Control(ContextFields<'a>),
+    /// Cancellation packet indicating the controls to cancel.
+    Cancel(IndicatorWords),
+    /// Validation acknowledge packet.
+    ValidationAck(Acknowledgement<'a>),
+    /// Execution acknowledge packet.
+    ExecutionAck(Acknowledgement<'a>),
+    /// Query-state acknowledge packet carrying the current state of the controllee.
+    QueryAck(ContextFields<'a>),
+}
+
+/// ControlleE or ControlleR identifier
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ControlId {
+    /// 32-bit identifier
+    Id(u32),
+    /// 128-bit Universally Unique Identifier
+    Uuid(u128),
+}
+
+impl ControlId {
+    /// Parser for an identifier in the 32-bit or UUID format.
+    fn parser<'a>(uuid: bool) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], ControlId> {
+        move |i| {
+            if uuid {
+                be_u128.map(ControlId::Uuid).parse(i)
+            } else {
+                be_u32.map(ControlId::Id).parse(i)
+            }
+        }
+    }
+
+    /// Number of bytes needed to encode the identifier.
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            ControlId::Id(id) => size_of_val(id),
+            ControlId::Uuid(uuid) => size_of_val(uuid),
+        }
+    }
+
+    /// Encode the identifier into `buf`, returning the number of bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.encoded_len();
+        ensure_capacity(buf, len)?;
+
+        match self {
+            ControlId::Id(id) => buf[..len].copy_from_slice(&id.to_be_bytes()),
+            ControlId::Uuid(uuid) => buf[..len].copy_from_slice(&uuid.to_be_bytes()),
+        }
+
+        Ok(len)
+    }
+}
+
+/// Control/Acknowledge Mode (CAM) field of a command packet.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Cam {
+    /// Is the ControlleE identifier included in the packet?
+    pub controllee_enable: bool,
+    /// Is the ControlleE identifier a 128-bit UUID rather than a 32-bit word?
+    pub controllee_uuid: bool,
+    /// Is the ControlleR identifier included in the packet?
+    pub controller_enable: bool,
+    /// Is the ControlleR identifier a 128-bit UUID rather than a 32-bit word?
+    pub controller_uuid: bool,
+    /// May the controllee apply part of the control when some fields cannot be applied?
+    pub permit_partial: bool,
+    /// May the controllee apply controls that produce warnings?
+    pub permit_warnings: bool,
+    /// May the controllee apply controls that produce errors?
+    pub permit_errors: bool,
+    /// Action the controllee takes on the control.
+    pub action_mode: ActionMode,
+    /// Are acknowledge packets only requested when there are warnings or errors?
+    pub nack_only: bool,
+    /// Validation acknowledge requested (control) or provided (acknowledge).
+    pub validation: bool,
+    /// Execution acknowledge requested (control) or provided (acknowledge).
+    pub execution: bool,
+    /// Query-state acknowledge requested (control) or provided (acknowledge).
+    pub query_state: bool,
+    /// Warnings requested (control) or included (acknowledge).
+    pub warnings: bool,
+    /// Errors requested (control) or included (acknowledge).
+    pub errors: bool,
+    /// Timing control mode, interpreting the packet timestamp.
+    pub timing_control: u8,
+    /// Was the control only partially applied? (acknowledge packets)
+    pub partial_action: bool,
+    /// Was the control scheduled rather than executed? (acknowledge packets)
+    pub scheduled_or_executed: bool,
+}
+
+impl TryFrom<u32> for Cam {
+    type Error = Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        let bit = |n: u32| (value >> n) & 0x01 != 0;
+
+        Ok(Cam {
+            controllee_enable: bit(31),
+            controllee_uuid: bit(30),
+            controller_enable: bit(29),
+            controller_uuid: bit(28),
+            permit_partial: bit(27),
+            permit_warnings: bit(26),
+            permit_errors: bit(25),
+            action_mode: ActionMode::try_from(((value >> 23) & 0b11) as u8)?,
+            nack_only: bit(22),
+            validation: bit(20),
+            execution: bit(19),
+            query_state: bit(18),
+            warnings: bit(17),
+            errors: bit(16),
+            timing_control: ((value >> 12) & 0b111) as u8,
+            partial_action: bit(11),
+            scheduled_or_executed: bit(10),
+        })
+    }
+}
+
+impl From<Cam> for u32 {
+    fn from(cam: Cam) -> u32 {
+        ((cam.controllee_enable as u32) << 31)
+            | ((cam.controllee_uuid as u32) << 30)
+            | ((cam.controller_enable as u32) << 29)
+            | ((cam.controller_uuid as u32) << 28)
+            | ((cam.permit_partial as u32) << 27)
+            | ((cam.permit_warnings as u32) << 26)
+            | ((cam.permit_errors as u32) << 25)
+            | ((u8::from(cam.action_mode) as u32) << 23)
+            | ((cam.nack_only as u32) << 22)
+            | ((cam.validation as u32) << 20)
+            | ((cam.execution as u32) << 19)
+            | ((cam.query_state as u32) << 18)
+            | ((cam.warnings as u32) << 17)
+            | ((cam.errors as u32) << 16)
+            | (((cam.timing_control & 0b111) as u32) << 12)
+            | ((cam.partial_action as u32) << 11)
+            | ((cam.scheduled_or_executed as u32) << 10)
+    }
+}
+
+/// Action Mode of a control packet
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ActionMode {
+    /// Take no action.
+    #[default]
+    NoAction,
+    /// Validate the control without applying it.
+    DryRun,
+    /// Apply the control.
+    Execute,
+}
+
+impl TryFrom<u8> for ActionMode {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ActionMode::NoAction),
+            1 => Ok(ActionMode::DryRun),
+            2 => Ok(ActionMode::Execute),
+            _ => Err(Error::ActionMode(value)),
+        }
+    }
+}
+
+impl From<ActionMode> for u8 {
+    fn from(action_mode: ActionMode) -> u8 {
+        match action_mode {
+            ActionMode::NoAction => 0,
+            ActionMode::DryRun => 1,
+            ActionMode::Execute => 2,
+        }
+    }
+}
+
+/// Warnings and errors reported by a validation or execution acknowledge packet.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Acknowledgement<'a> {
+    /// Warning fields, when included
+    pub warnings: Option<AckFields<'a>>,
+    /// Error fields, when included
+    pub errors: Option<AckFields<'a>>,
+}
+
+impl<'a> Acknowledgement<'a> {
+    /// Parse the WIF and EIF words and the fields they indicate
+    fn parse(cam: &Cam, i: &'a [u8]) -> IResult<&'a [u8], Acknowledgement<'a>> {
+        let (i, wif) = cond(cam.warnings, IndicatorWords::parse).parse(i)?;
+        let (i, eif) = cond(cam.errors, IndicatorWords::parse).parse(i)?;
+        let (i, warnings) =
+            cond(cam.warnings, AckFields::parser(wif.unwrap_or_default())).parse(i)?;
+        let (i, errors) = cond(cam.errors, AckFields::parser(eif.unwrap_or_default())).parse(i)?;
+
+        Ok((i, Acknowledgement { warnings, errors }))
+    }
+}
+
+/// Warning or error fields of an acknowledge packet, one per indicated context field.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AckFields<'a> {
+    /// Indicator words (WIF or EIF) of the context fields with warnings or errors.
+    pub indicators: IndicatorWords,
+    fields: &'a [u8],
+}
+
+impl<'a> AckFields<'a> {
+    /// Parser for the fields indicated by `indicators`.
+    fn parser(indicators: IndicatorWords) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], AckFields<'a>> {
+        move |i| {
+            let len = indicators.fields().count() * size_of::<u32>();
+            let (i, fields) = take(len)(i)?;
+            Ok((i, AckFields { indicators, fields }))
+        }
+    }
+
+    /// Iterate over the `(word, bit)` position of each indicated context field and its flags.
+    pub fn iter(&self) -> impl Iterator<Item = ((u8, u8), AckFlags)> + 'a {
+        let flags = self.fields.chunks_exact(size_of::<u32>()).map(|chunk| {
+            AckFlags::from(u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        });
+        self.indicators.fields().zip(flags)
+    }
+
+    /// Flags reported for the context field at bit `bit` of indicator word `word`.
+    pub fn get(&self, word: u8, bit: u8) -> Option<AckFlags> {
+        self.iter()
+            .find(|&(position, _)| position == (word, bit))
+            .map(|(_, flags)| flags)
+    }
+}
+
+/// Warning or error flags reported for a context field.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AckFlags {
+    /// The field was not executed.
+    pub field_not_executed: bool,
+    /// A device failure occurred.
+    pub device_failure: bool,
+    /// The field is erroneous.
+    pub erroneous_field: bool,
+    /// The parameter is out of range.
+    pub parameter_out_of_range: bool,
+    /// The parameter precision is not supported.
+    pub parameter_unsupported_precision: bool,
+    /// The field value is invalid.
+    pub field_value_invalid: bool,
+    /// There is a problem with the timestamp.
+    pub timestamp_problem: bool,
+    /// The control results in hazardous power levels.
+    pub hazardous_power_levels: bool,
+    /// The control results in distortion.
+    pub distortion: bool,
+    /// The control violates in-band power compliance.
+    pub in_band_power_compliance: bool,
+    /// The control violates out-of-band power compliance.
+    pub out_of_band_power_compliance: bool,
+    /// The control causes co-site interference.
+    pub cosite_interference: bool,
+    /// The control causes regional interference.
+    pub regional_interference: bool,
+    /// User-defined flags
+    pub user_defined: u16,
+}
+
+impl From<u32> for AckFlags {
+    fn from(value: u32) -> Self {
+        let bit = |n: u32| (value >> n) & 0x01 != 0;
+
+        AckFlags {
+            field_not_executed: bit(31),
+            device_failure: bit(30),
+            erroneous_field: bit(29),
+            parameter_out_of_range: bit(28),
+            parameter_unsupported_precision: bit(27),
+            field_value_invalid: bit(26),
+            timestamp_problem: bit(25),
+            hazardous_power_levels: bit(24),
+            distortion: bit(23),
+            in_band_power_compliance: bit(22),
+            out_of_band_power_compliance: bit(21),
+            cosite_interference: bit(20),
+            regional_interference: bit(19),
+            user_defined: (value & 0xfff) as u16,
+        }
+    }
+}
+
+impl From<AckFlags> for u32 {
+    fn from(flags: AckFlags) -> u32 {
+        ((flags.field_not_executed as u32) << 31)
+            | ((flags.device_failure as u32) << 30)
+            | ((flags.erroneous_field as u32) << 29)
+            | ((flags.parameter_out_of_range as u32) << 28)
+            | ((flags.parameter_unsupported_precision as u32) << 27)
+            | ((flags.field_value_invalid as u32) << 26)
+            | ((flags.timestamp_problem as u32) << 25)
+            | ((flags.hazardous_power_levels as u32) << 24)
+            | ((flags.distortion as u32) << 23)
+            | ((flags.in_band_power_compliance as u32) << 22)
+            | ((flags.out_of_band_power_compliance as u32) << 21)
+            | ((flags.cosite_interference as u32) << 20)
+            | ((flags.regional_interference as u32) << 19)
+            | (flags.user_defined & 0xfff) as u32
+    }
+}
@@ -0,0 +1,220 @@
+use nom::{combinator::cond, number::complete::be_u32, Err, IResult, Parser};
+
+use super::*;
+use crate::Error;
+
+/// VRT IF Context Packet
+#[derive(Debug, PartialEq)]
+pub struct ContextPacket<'a> {
+    /// VRT Packet Header
+    pub header: Header,
+    /// Stream Id
+    pub stream_id: u32,
+    /// Optional Class Id
+    pub class_id: Option<ClassId>,
+    /// Optional Integer-Seconds Timestamp
+    pub tsi: Option<u32>,
+    /// Optional Fractional-Seconds Timestamp
+    pub tsf: Option<u64>,
+    /// Context fields indicated by CIF0
+    pub cif0: Cif0<'a>,
+    /// Context fields indicated by CIF1, when enabled by CIF0
+    pub cif1: Option<Cif1<'a>>,
+    /// Context fields indicated by CIF2, when enabled by CIF0
+    pub cif2: Option<Cif2>,
+    /// Context fields indicated by CIF3, when enabled by CIF0
+    pub cif3: Option<Cif3>,
+}
+
+impl<'a> ContextPacket<'a> {
+    /// Parse the VRT context packet
+    pub fn parse(i: &'a [u8]) -> IResult<&'a [u8], ContextPacket<'a>> {
+        let (i, packet) = VrtPacket::parse(i)?;
+        let packet = ContextPacket::from_packet(packet)?;
+
+        Ok((i, packet))
+    }
+
+    /// Parse the VRT context packet at the start of `bytes`, returning it along with its length
+    /// in bytes.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<(ContextPacket<'a>, usize), Error> {
+        let (packet, len) = VrtPacket::from_bytes(bytes)?;
+        let offset = packet.payload.as_ptr() as usize - bytes.as_ptr() as usize;
+        let packet = ContextPacket::try_from(packet).map_err(|e| e.offset_by(offset))?;
+
+        Ok((packet, len))
+    }
+
+    /// Decode the context fields carried in the payload of an IF Context packet.
+    fn from_packet(packet: VrtPacket<'a>) -> Result<Self, Err<nom::error::Error<&'a [u8]>>> {
+        let verify = || {
+            Err::Error(nom::error::Error::new(
+                packet.payload,
+                nom::error::ErrorKind::Verify,
+            ))
+        };
+
+        if packet.header.packet_type != PktType::IfContext {
+            return Err(verify());
+        }
+        let stream_id = packet.stream_id.ok_or_else(verify)?;
+
+        let (_, fields) = ContextFields::parse(packet.payload)?;
+
+        Ok(ContextPacket {
+            header: packet.header,
+            stream_id,
+            class_id: packet.class_id,
+            tsi: packet.tsi,
+            tsf: packet.tsf,
+            cif0: fields.cif0,
+            cif1: fields.cif1,
+            cif2: fields.cif2,
+            cif3: fields.cif3,
+        })
+    }
+}
+
+impl<'a> TryFrom<VrtPacket<'a>> for ContextPacket<'a> {
+    type Error = Error;
+
+    /// Decode the context fields carried in the payload of an IF Context packet.
+    ///
+    /// Extension Context packets, whose payload is defined by their packet class, are rejected
+    /// with [`Error::UnsupportedPacketType`] and packets using VITA 49.2 Field Attributes (CIF7)
+    /// with [`Error::FieldAttributes`]. Invalid fields are reported with their byte offset from
+    /// the start of the payload.
+    fn try_from(packet: VrtPacket<'a>) -> Result<Self, Error> {
+        let packet_type = packet.header.packet_type;
+        if packet_type != PktType::IfContext {
+            return Err(Error::UnsupportedPacketType(packet_type.into()));
+        }
+        if packet.stream_id.is_none() {
+            return Err(Error::HeaderMismatch("stream_id"));
+        }
+        // Bit 7 of CIF0 enables CIF7.
+        if packet.payload.get(3).is_some_and(|byte| byte & 0x80 != 0) {
+            return Err(Error::FieldAttributes);
+        }
+        let payload = packet.payload;
+        ContextPacket::from_packet(packet)
+            .map_err(|e| Error::from_nom(payload, e, "context fields"))
+    }
+}
+
+/// Context fields indicated by the CIF0 through CIF3 words.
+#[derive(Debug, PartialEq)]
+pub struct ContextFields<'a> {
+    /// Context fields indicated by CIF0
+    pub cif0: Cif0<'a>,
+    /// Context fields indicated by CIF1, when enabled by CIF0
+    pub cif1: Option<Cif1<'a>>,
+    /// Context fields indicated by CIF2, when enabled by CIF0
+    pub cif2: Option<Cif2>,
+    /// Context fields indicated by CIF3, when enabled by CIF0
+    pub cif3: Option<Cif3>,
+}
+
+impl<'a> ContextFields<'a> {
+    /// Parse the CIF words and the context fields they indicate
+    ///
+    /// VITA 49.2 Field Attributes (CIF7) are rejected.
+    pub fn parse(i: &'a [u8]) -> IResult<&'a [u8], ContextFields<'a>> {
+        let (i, cif) = IndicatorWords::parse(i)?;
+
+        let (i, cif0) = Cif0::parse_fields(cif.word0, i)?;
+        let (i, cif1) = match cif.word1 {
+            Some(word) => {
+                let (i, cif1) = Cif1::parse_fields(word, i)?;
+                (i, Some(cif1))
+            }
+            None => (i, None),
+        };
+        let (i, cif2) = match cif.word2 {
+            Some(word) => {
+                let (i, cif2) = Cif2::parse_fields(word, i)?;
+                (i, Some(cif2))
+            }
+            None => (i, None),
+        };
+        let (i, cif3) = match cif.word3 {
+            Some(word) => {
+                let (i, cif3) = Cif3::parse_fields(word, i)?;
+                (i, Some(cif3))
+            }
+            None => (i, None),
+        };
+
+        Ok((
+            i,
+            ContextFields {
+                cif0,
+                cif1,
+                cif2,
+                cif3,
+            },
+        ))
+    }
+}
+
+/// Indicator words 0 through 3 of a CIF, WIF or EIF set.
+///
+/// Bits 1, 2 and 3 of word 0 enable words 1, 2 and 3 respectively.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct IndicatorWords {
+    /// Indicator word 0
+    pub word0: u32,
+    /// Indicator word 1, when enabled by word 0
+    pub word1: Option<u32>,
+    /// Indicator word 2, when enabled by word 0
+    pub word2: Option<u32>,
+    /// Indicator word 3, when enabled by word 0
+    pub word3: Option<u32>,
+}
+
+impl IndicatorWords {
+    /// Parse the indicator words
+    ///
+    /// VITA 49.2 Field Attributes (indicator word 7) are rejected with an `ErrorKind::Verify`
+    /// error; [`ContextPacket::try_from`] reports them as [`Error::FieldAttributes`].
+    pub fn parse(i: &[u8]) -> IResult<&[u8], IndicatorWords> {
+        let (i, word0) = be_u32(i)?;
+        if (word0 >> 7) & 0x01 != 0 {
+            return Err(Err::Error(nom::error::Error::new(
+                i,
+                nom::error::ErrorKind::Verify,
+            )));
+        }
+        let bit = |n: u32| (word0 >> n) & 0x01 != 0;
+        let (i, word1) = cond(bit(1), be_u32).parse(i)?;
+        let (i, word2) = cond(bit(2), be_u32).parse(i)?;
+        let (i, word3) = cond(bit(3), be_u32).parse(i)?;
+
+        Ok((
+            i,
+            IndicatorWords {
+                word0,
+                word1,
+                word2,
+                word3,
+            },
+        ))
+    }
+
+    /// Iterate over the `(word, bit)` position of each indicated field, in packet order.
+    pub fn fields(&self) -> impl Iterator<Item = (u8, u8)> {
+        // Bits 7..0 of word 0 enable other indicator words rather than fields.
+        let words = [
+            self.word0 & !0xff,
+            self.word1.unwrap_or(0),
+            self.word2.unwrap_or(0),
+            self.word3.unwrap_or(0),
+        ];
+        (0..4u8).flat_map(move |word| {
+            (0..32u8)
+                .rev()
+                .filter(move |&bit| (words[word as usize] >> bit) & 0x01 != 0)
+                .map(move |bit| (word, bit))
+        })
+    }
+}
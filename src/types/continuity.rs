@@ -0,0 +1,149 @@
+use alloc::collections::BTreeMap;
+use core::{cmp::Ordering, time::Duration};
+
+use super::*;
+
+/// Continuity of a packet's count relative to the previous packet of its stream.
+///
+/// With a modulo-16 packet count, a jump of up to 8 counts is taken as dropped packets and a
+/// larger jump as a packet arriving after later ones.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Continuity {
+    /// First packet seen on the stream.
+    First,
+    /// The packet count follows the previous packet.
+    InOrder,
+    /// The given number of packets are missing before this one.
+    Dropped(u8),
+    /// The packet count repeats the previous packet.
+    Duplicate,
+    /// The packet count is behind the previous packet.
+    Reordered,
+}
+
+/// Continuity findings for a single packet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ContinuityReport {
+    /// Continuity of the packet count
+    pub continuity: Continuity,
+    /// Does the trailer report a sample discontinuity within the packet?
+    pub sample_loss: bool,
+    /// Does the timestamp go backwards or jump further than the configured maximum gap?
+    pub timestamp_jump: bool,
+}
+
+/// Running continuity totals of a stream.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ContinuityStats {
+    /// Number of packets received
+    pub packets: u64,
+    /// Number of packets found missing
+    pub dropped: u64,
+    /// Number of duplicate packets
+    pub duplicated: u64,
+    /// Number of packets received out of order
+    pub reordered: u64,
+    /// Number of packets whose trailer reports sample loss
+    pub sample_loss: u64,
+    /// Number of timestamp jumps
+    pub timestamp_jumps: u64,
+}
+
+/// Continuity state of one stream.
+#[derive(Clone, Copy, Debug)]
+struct StreamState {
+    packet_count: u8,
+    timestamp: Option<Timestamp>,
+    stats: ContinuityStats,
+}
+
+/// Checks the packet-count continuity of each stream and packet type.
+#[derive(Clone, Debug, Default)]
+pub struct ContinuityChecker {
+    streams: BTreeMap<(Option<u32>, u8), StreamState>,
+    max_timestamp_gap: Option<Duration>,
+}
+
+impl ContinuityChecker {
+    /// Create a checker without any streams.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also check that timestamps never go backwards and that consecutive real-time
+    /// timestamps are at most `gap` apart.
+    pub fn max_timestamp_gap(mut self, gap: Duration) -> Self {
+        self.max_timestamp_gap = Some(gap);
+        self
+    }
+
+    /// Check the continuity of `packet` against the previous packet of its stream.
+    pub fn check(&mut self, packet: &VrtPacket<'_>) -> ContinuityReport {
+        let key = (packet.stream_id, u8::from(packet.header.packet_type));
+        let packet_count = packet.header.packet_count & 0xf;
+        let timestamp = packet.timestamp();
+
+        let continuity = match self.streams.get(&key) {
+            None => Continuity::First,
+            Some(state) => match packet_count.wrapping_sub(state.packet_count) & 0xf {
+                0 => Continuity::Duplicate,
+                1 => Continuity::InOrder,
+                n @ 2..=8 => Continuity::Dropped(n - 1),
+                _ => Continuity::Reordered,
+            },
+        };
+        let state = self.streams.entry(key).or_insert(StreamState {
+            packet_count,
+            timestamp,
+            stats: ContinuityStats::default(),
+        });
+
+        let sample_loss = packet
+            .trailer
+            .is_some_and(|trailer| trailer.sample_loss() == Some(true));
+        let advanced = matches!(continuity, Continuity::InOrder | Continuity::Dropped(_));
+        let timestamp_jump = advanced
+            && match (self.max_timestamp_gap, state.timestamp, timestamp) {
+                (Some(gap), Some(previous), Some(current)) => {
+                    current.partial_cmp(&previous) == Some(Ordering::Less)
+                        || current
+                            .duration_since(&previous)
+                            .is_some_and(|elapsed| elapsed > gap)
+                }
+                _ => false,
+            };
+
+        let stats = &mut state.stats;
+        stats.packets += 1;
+        match continuity {
+            Continuity::Dropped(n) => stats.dropped += n as u64,
+            Continuity::Duplicate => stats.duplicated += 1,
+            Continuity::Reordered => stats.reordered += 1,
+            Continuity::First | Continuity::InOrder => {}
+        }
+        stats.sample_loss += sample_loss as u64;
+        stats.timestamp_jumps += timestamp_jump as u64;
+        if advanced {
+            state.packet_count = packet_count;
+            state.timestamp = timestamp;
+        }
+
+        ContinuityReport {
+            continuity,
+            sample_loss,
+            timestamp_jump,
+        }
+    }
+
+    /// Continuity totals of the stream `stream_id` of packets of type `packet_type`.
+    pub fn stats(&self, stream_id: Option<u32>, packet_type: PktType) -> Option<&ContinuityStats> {
+        self.streams
+            .get(&(stream_id, u8::from(packet_type)))
+            .map(|state| &state.stats)
+    }
+
+    /// Forget all streams, for example after reconnecting.
+    pub fn clear(&mut self) {
+        self.streams.clear();
+    }
+}
@@ -0,0 +1,138 @@
+use nom::IResult;
+
+/// Define a fixed-point newtype over a two's-complement or unsigned integer.
+macro_rules! fixed_point {
+    ($(#[$meta:meta])* $name:ident($raw:ty, $radix:expr, $parse:path)) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub struct $name($raw);
+
+        impl $name {
+            /// Number of bits to the right of the radix point.
+            pub const RADIX: u32 = $radix;
+
+            /// Create a value from its raw fixed-point representation.
+            pub const fn from_raw(raw: $raw) -> Self {
+                $name(raw)
+            }
+
+            /// The raw fixed-point representation.
+            pub const fn raw(self) -> $raw {
+                self.0
+            }
+
+            /// Convert to a floating-point value.
+            pub fn to_f64(self) -> f64 {
+                self.0 as f64 / (1u64 << Self::RADIX) as f64
+            }
+
+            /// Convert from a floating-point value, rounding to the nearest representable value.
+            ///
+            /// Values outside the representable range saturate.
+            pub fn from_f64(value: f64) -> Self {
+                let scaled = value * (1u64 << Self::RADIX) as f64;
+                // Round half away from zero; `as` saturates out-of-range values.
+                let rounded = if scaled < 0.0 { scaled - 0.5 } else { scaled + 0.5 };
+                $name(rounded as $raw)
+            }
+
+            /// Parse the big-endian fixed-point value
+            pub fn parse(i: &[u8]) -> IResult<&[u8], Self> {
+                let (i, raw) = $parse(i)?;
+                Ok((i, $name(raw)))
+            }
+
+            /// The big-endian encoding of the value.
+            pub const fn to_be_bytes(self) -> [u8; size_of::<$raw>()] {
+                self.0.to_be_bytes()
+            }
+
+            /// Create a value from its big-endian encoding.
+            pub const fn from_be_bytes(bytes: [u8; size_of::<$raw>()]) -> Self {
+                $name(<$raw>::from_be_bytes(bytes))
+            }
+        }
+
+        impl From<$raw> for $name {
+            fn from(raw: $raw) -> Self {
+                $name(raw)
+            }
+        }
+
+        impl From<$name> for $raw {
+            fn from(value: $name) -> $raw {
+                value.0
+            }
+        }
+    };
+}
+
+fixed_point!(
+    /// Frequency in Hz, 64-bit fixed point with a radix point at bit 20.
+    Frequency(i64, 20, nom::number::complete::be_i64)
+);
+
+fixed_point!(
+    /// Gain or level in dB (or dBm), 16-bit fixed point with a radix point at bit 7.
+    Gain(i16, 7, nom::number::complete::be_i16)
+);
+
+fixed_point!(
+    /// Temperature in degrees Celsius, 16-bit fixed point with a radix point at bit 6.
+    Temperature(i16, 6, nom::number::complete::be_i16)
+);
+
+fixed_point!(
+    /// Geolocation angle in degrees, 32-bit fixed point with a radix point at bit 22.
+    Angle(i32, 22, nom::number::complete::be_i32)
+);
+
+fixed_point!(
+    /// Pointing or beam angle in degrees, 16-bit fixed point with a radix point at bit 7.
+    PointingAngle(i16, 7, nom::number::complete::be_i16)
+);
+
+fixed_point!(
+    /// Azimuthal angle in degrees, unsigned 16-bit fixed point with a radix point at bit 7.
+    Azimuth(u16, 7, nom::number::complete::be_u16)
+);
+
+fixed_point!(
+    /// Phase in radians, 16-bit fixed point with a radix point at bit 7.
+    Phase(i16, 7, nom::number::complete::be_i16)
+);
+
+fixed_point!(
+    /// Polarization angle in radians, 16-bit fixed point with a radix point at bit 13.
+    PolarizationAngle(i16, 13, nom::number::complete::be_i16)
+);
+
+fixed_point!(
+    /// Distance in meters, 32-bit fixed point with a radix point at bit 5.
+    Distance(i32, 5, nom::number::complete::be_i32)
+);
+
+fixed_point!(
+    /// Speed in meters per second, 32-bit fixed point with a radix point at bit 16.
+    Speed(i32, 16, nom::number::complete::be_i32)
+);
+
+fixed_point!(
+    /// Range (distance) in meters, unsigned 32-bit fixed point with a radix point at bit 6.
+    RangeDistance(u32, 6, nom::number::complete::be_u32)
+);
+
+fixed_point!(
+    /// Bit error rate, 16-bit fixed point with a radix point at bit 7.
+    BitErrorRate(i16, 7, nom::number::complete::be_i16)
+);
+
+fixed_point!(
+    /// Relative humidity in percent, unsigned 16-bit fixed point with a radix point at bit 7.
+    Humidity(u16, 7, nom::number::complete::be_u16)
+);
+
+fixed_point!(
+    /// Pressure in hectopascals, unsigned 32-bit fixed point with a radix point at bit 6.
+    Pressure(u32, 6, nom::number::complete::be_u32)
+);
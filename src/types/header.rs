@@ -1,6 +1,10 @@
-use nom::{number::streaming::be_u8, Err, IResult, Needed};
+use nom::{
+    number::streaming::{be_u16, be_u8},
+    Err, IResult, Needed,
+};
 
 use super::*;
+use crate::Error;
 
 /// VRT Packet Header
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -9,8 +13,8 @@ pub struct Header {
     pub packet_type: PktType,
     /// Is the Class Identifier (Class ID) field included in the packet?
     pub c: bool,
-    /// Is the VRT Packet Trailer included in the packet?
-    pub t: bool,
+    /// Packet-specific indicator bits
+    pub indicators: Indicators,
     /// Type of Integer-seconds Timestamp included in the packet.
     pub tsi: Tsi,
     /// Type of Fractional-seconds Timestamp included in the packet.
@@ -22,17 +26,62 @@ pub struct Header {
 }
 
 impl Header {
+    /// Size of the encoded header in bytes.
+    pub const SIZE: usize = size_of::<u32>();
+
+    /// Is the VRT Packet Trailer included in the packet?
+    pub fn has_trailer(&self) -> bool {
+        matches!(self.indicators, Indicators::Data { trailer: true, .. })
+    }
+
+    /// Number of bytes in the header, the optional fields and the trailer it indicates.
+    pub(crate) fn fields_len(&self) -> usize {
+        Self::SIZE
+            + self.packet_type.has_stream_id() as usize * size_of::<u32>()
+            + self.c as usize * ClassId::SIZE
+            + (self.tsi != Tsi::None) as usize * size_of::<u32>()
+            + (self.tsf != Tsf::None) as usize * size_of::<u64>()
+            + self.has_trailer() as usize * Trailer::SIZE
+    }
+
+    /// Name of the field at byte `offset` of a packet with this header.
+    pub(crate) fn field_at(&self, offset: usize) -> &'static str {
+        let fields = [
+            ("header", Self::SIZE),
+            (
+                "stream id",
+                self.packet_type.has_stream_id() as usize * size_of::<u32>(),
+            ),
+            ("class id", self.c as usize * ClassId::SIZE),
+            ("tsi", (self.tsi != Tsi::None) as usize * size_of::<u32>()),
+            ("tsf", (self.tsf != Tsf::None) as usize * size_of::<u64>()),
+        ];
+        let mut end = 0;
+        for (field, len) in fields {
+            end += len;
+            if offset < end {
+                return field;
+            }
+        }
+        let packet_len = self.packet_size as usize * size_of::<u32>();
+        if offset + self.has_trailer() as usize * Trailer::SIZE < packet_len {
+            "payload"
+        } else {
+            "trailer"
+        }
+    }
+
     /// Parse the VRT packet header
     pub fn parse(i: &[u8]) -> IResult<&[u8], Header> {
         if i.len() < 4 {
-            return Err(Err::Incomplete(Needed::new(4)));
+            return Err(Err::Incomplete(Needed::new(Self::SIZE - i.len())));
         }
 
         let (i, first_byte) = be_u8(i)?;
         let packet_type = PktType::try_from((first_byte >> 4) & 0b1111)
             .map_err(|_| Err::Error(nom::error::Error::new(i, nom::error::ErrorKind::Verify)))?;
         let c = ((first_byte >> 3) & 0x01) != 0;
-        let t = ((first_byte >> 2) & 0x01) != 0;
+        let indicators = Indicators::from_bits(packet_type, first_byte & 0b111);
 
         let (i, second_byte) = be_u8(i)?;
         let tsi = Tsi::try_from((second_byte >> 6) & 0b11)
@@ -41,14 +90,12 @@ impl Header {
             .map_err(|_| Err::Error(nom::error::Error::new(i, nom::error::ErrorKind::Verify)))?;
         let packet_count: u8 = (second_byte) & 0xf;
 
-        let (i, third_byte) = be_u8(i)?;
-        let (i, fourth_byte) = be_u8(i)?;
-        let packet_size: u16 = (third_byte + fourth_byte) as u16;
+        let (i, packet_size) = be_u16(i)?;
 
         let hdr = Header {
             packet_type,
             c,
-            t,
+            indicators,
             tsi,
             tsf,
             packet_count,
@@ -56,4 +103,15 @@ impl Header {
         };
         Ok((i, hdr))
     }
+
+    /// Encode the VRT packet header into `buf`, returning the number of bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        ensure_capacity(buf, Self::SIZE)?;
+
+        buf[0] = (u8::from(self.packet_type) << 4) | ((self.c as u8) << 3) | self.indicators.bits();
+        buf[1] = (u8::from(self.tsi) << 6) | (u8::from(self.tsf) << 4) | (self.packet_count & 0xf);
+        buf[2..4].copy_from_slice(&self.packet_size.to_be_bytes());
+
+        Ok(Self::SIZE)
+    }
 }
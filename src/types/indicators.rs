@@ -0,0 +1,80 @@
+use super::*;
+
+/// Packet-specific indicator bits (bits 26 to 24 of the header word), interpreted by packet type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Indicators {
+    /// Indicators of IF Data and Extension Data packets
+    Data {
+        /// Is the VRT Packet Trailer included in the packet?
+        trailer: bool,
+        /// Does the packet use VITA 49.2 features that are not part of VITA 49.0?
+        not_v49_0: bool,
+        /// Does the payload carry signal spectrum rather than signal time-domain data?
+        spectrum: bool,
+    },
+    /// Indicators of IF Context and Extension Context packets
+    Context {
+        /// Does the packet use VITA 49.2 features that are not part of VITA 49.0?
+        not_v49_0: bool,
+        /// Timestamp Mode: does the timestamp convey general rather than precise timing?
+        tsm: bool,
+    },
+    /// Indicators of Command and Extension Command packets
+    Command {
+        /// Is this an acknowledge packet?
+        acknowledge: bool,
+        /// Is this a cancellation packet?
+        cancel: bool,
+    },
+}
+
+impl Indicators {
+    /// Indicators of `packet_type` with every bit clear.
+    pub fn new(packet_type: PktType) -> Self {
+        Self::from_bits(packet_type, 0)
+    }
+
+    /// Interpret the three indicator bits, right-justified in `bits`, for `packet_type`.
+    pub fn from_bits(packet_type: PktType, bits: u8) -> Self {
+        let bit = |n: u8| (bits >> n) & 0x01 != 0;
+
+        if packet_type.is_command() {
+            Indicators::Command {
+                acknowledge: bit(2),
+                cancel: bit(0),
+            }
+        } else if packet_type.is_context() {
+            Indicators::Context {
+                not_v49_0: bit(1),
+                tsm: bit(0),
+            }
+        } else {
+            Indicators::Data {
+                trailer: bit(2),
+                not_v49_0: bit(1),
+                spectrum: bit(0),
+            }
+        }
+    }
+
+    /// The three indicator bits, right-justified.
+    pub fn bits(self) -> u8 {
+        match self {
+            Indicators::Data {
+                trailer,
+                not_v49_0,
+                spectrum,
+            } => ((trailer as u8) << 2) | ((not_v49_0 as u8) << 1) | spectrum as u8,
+            Indicators::Context { not_v49_0, tsm } => ((not_v49_0 as u8) << 1) | tsm as u8,
+            Indicators::Command {
+                acknowledge,
+                cancel,
+            } => ((acknowledge as u8) << 2) | cancel as u8,
+        }
+    }
+
+    /// Do these indicators apply to `packet_type`?
+    pub fn matches(self, packet_type: PktType) -> bool {
+        core::mem::discriminant(&self) == core::mem::discriminant(&Self::new(packet_type))
+    }
+}
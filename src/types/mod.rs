@@ -4,18 +4,109 @@
 //!
 //! [VITA 49.0-2015 VITA Radio Transport (VRT) Standard]: https://vitastore.dpdcart.com/product/168632
 
+mod builder;
+#[cfg(feature = "alloc")]
+mod capture;
+mod cif0;
+mod cif1;
+mod cif2;
+mod cif3;
 mod class_id;
+mod command;
+mod context;
+#[cfg(feature = "alloc")]
+mod continuity;
+mod fixed;
 mod header;
+mod indicators;
+#[cfg(feature = "alloc")]
+mod owned;
 mod packet;
+mod payload_format;
 mod pkt_type;
+#[cfg(feature = "alloc")]
+mod registry;
+mod samples;
+#[cfg(feature = "std")]
+mod socket;
+#[cfg(feature = "alloc")]
+mod stream_decoder;
+#[cfg(feature = "alloc")]
+mod stream_tracker;
+mod timestamp;
 mod trailer;
 mod tsf;
 mod tsi;
+#[cfg(feature = "tokio")]
+mod udp_stream;
+#[cfg(feature = "alloc")]
+mod validate;
+mod vrl;
 
+pub use builder::*;
+#[cfg(feature = "alloc")]
+pub use capture::*;
+pub use cif0::*;
+pub use cif1::*;
+pub use cif2::*;
+pub use cif3::*;
 pub use class_id::*;
+pub use command::*;
+pub use context::*;
+#[cfg(feature = "alloc")]
+pub use continuity::*;
+pub use fixed::*;
 pub use header::*;
+pub use indicators::*;
+#[cfg(feature = "alloc")]
+pub use owned::*;
 pub use packet::*;
+pub use payload_format::*;
 pub use pkt_type::*;
+#[cfg(feature = "alloc")]
+pub use registry::*;
+pub use samples::*;
+#[cfg(feature = "std")]
+pub use socket::*;
+#[cfg(feature = "alloc")]
+pub use stream_decoder::*;
+#[cfg(feature = "alloc")]
+pub use stream_tracker::*;
+pub use timestamp::*;
 pub use trailer::*;
 pub use tsf::*;
 pub use tsi::*;
+#[cfg(feature = "tokio")]
+pub use udp_stream::*;
+#[cfg(feature = "alloc")]
+pub use validate::*;
+pub use vrl::*;
+
+/// Check that `buf` can hold `needed` bytes of encoded output.
+pub(crate) fn ensure_capacity(buf: &[u8], needed: usize) -> Result<(), crate::Error> {
+    if buf.len() < needed {
+        return Err(crate::Error::BufferTooSmall {
+            needed,
+            available: buf.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Parse a 32-bit context field word whose upper 16 bits are reserved.
+pub(crate) fn lower_i16(i: &[u8]) -> nom::IResult<&[u8], i16> {
+    let (i, _reserved) = nom::number::complete::be_u16(i)?;
+    nom::number::complete::be_i16(i)
+}
+
+/// Parse a 32-bit context field word whose upper 16 bits are reserved.
+pub(crate) fn lower_u16(i: &[u8]) -> nom::IResult<&[u8], u16> {
+    let (i, _reserved) = nom::number::complete::be_u16(i)?;
+    nom::number::complete::be_u16(i)
+}
+
+/// Parse a 32-bit context field word holding an 8-bit reserved field and a 24-bit OUI.
+pub(crate) fn oui(i: &[u8]) -> nom::IResult<&[u8], Oui> {
+    let (i, word) = nom::number::complete::be_u32(i)?;
+    Ok((i, Oui::from_word(word)))
+}
@@ -0,0 +1,91 @@
+use alloc::vec::Vec;
+
+use nom::IResult;
+
+use super::*;
+use crate::Error;
+
+/// VRT Packet that owns its data payload.
+///
+/// Unlike [`VrtPacket`], it does not borrow the receive buffer, so it can be stored or sent
+/// to another thread.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnedVrtPacket {
+    /// VRT Packet Header
+    pub header: Header,
+    /// Optional Stream Id
+    pub stream_id: Option<u32>,
+    /// Optional Class Id
+    pub class_id: Option<ClassId>,
+    /// Optional Integer-Seconds Timestamp
+    pub tsi: Option<u32>,
+    /// Optional Fractional-Seconds Timestamp
+    pub tsf: Option<u64>,
+    /// Data Payload
+    pub payload: Vec<u8>,
+    /// Optional VRT Packet Trailer
+    pub trailer: Option<Trailer>,
+}
+
+impl OwnedVrtPacket {
+    /// Parse the VRT packet, copying its data payload
+    pub fn parse(i: &[u8]) -> IResult<&[u8], OwnedVrtPacket> {
+        let (i, packet) = VrtPacket::parse(i)?;
+        Ok((i, packet.to_owned_packet()))
+    }
+
+    /// Borrow the packet as a [`VrtPacket`].
+    pub fn as_packet(&self) -> VrtPacket<'_> {
+        VrtPacket {
+            header: self.header,
+            stream_id: self.stream_id,
+            class_id: self.class_id,
+            tsi: self.tsi,
+            tsf: self.tsf,
+            payload: &self.payload,
+            trailer: self.trailer,
+        }
+    }
+
+    /// Number of bytes needed to encode the VRT packet
+    pub fn encoded_len(&self) -> usize {
+        self.as_packet().encoded_len()
+    }
+
+    /// Encode the VRT packet into `buf`, returning the number of bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.as_packet().encode(buf)
+    }
+
+    /// Encode the VRT packet into a newly allocated buffer.
+    pub fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        self.as_packet().encode_to_vec()
+    }
+}
+
+impl VrtPacket<'_> {
+    /// Copy the packet into an [`OwnedVrtPacket`].
+    pub fn to_owned_packet(&self) -> OwnedVrtPacket {
+        OwnedVrtPacket {
+            header: self.header,
+            stream_id: self.stream_id,
+            class_id: self.class_id,
+            tsi: self.tsi,
+            tsf: self.tsf,
+            payload: self.payload.to_vec(),
+            trailer: self.trailer,
+        }
+    }
+}
+
+impl From<VrtPacket<'_>> for OwnedVrtPacket {
+    fn from(packet: VrtPacket<'_>) -> Self {
+        packet.to_owned_packet()
+    }
+}
+
+impl<'a> From<&'a OwnedVrtPacket> for VrtPacket<'a> {
+    fn from(packet: &'a OwnedVrtPacket) -> Self {
+        packet.as_packet()
+    }
+}
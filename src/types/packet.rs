@@ -1,12 +1,19 @@
 use nom::{
+    bytes::streaming::take,
+    combinator::cond,
+    error::ErrorKind,
     number::streaming::{be_u32, be_u64},
-    Err, IResult, Needed,
+    Err, IResult, Needed, Parser,
 };
 
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
 use super::*;
+use crate::Error;
 
 /// VRT Packet
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct VrtPacket<'a> {
     /// VRT Packet Header
     pub header: Header,
@@ -24,67 +31,39 @@ pub struct VrtPacket<'a> {
     pub trailer: Option<Trailer>,
 }
 
-impl VrtPacket<'_> {
+impl<'a> VrtPacket<'a> {
+    /// Start building a packet of the given type with [`VrtPacketBuilder`].
+    pub fn builder(packet_type: PktType) -> VrtPacketBuilder<'a> {
+        VrtPacketBuilder::new(packet_type)
+    }
+
     /// Parse the VRT packet
+    ///
+    /// Fails with [`ErrorKind::Verify`] if the packet size is too small to hold the header
+    /// fields and trailer indicated by the header.
     pub fn parse(i: &[u8]) -> IResult<&[u8], VrtPacket<'_>> {
+        let packet = i;
         let (i, header) = Header::parse(i)?;
 
         let expected_size = header.packet_size as usize * size_of::<u32>();
-        let actual_size = i.len() + size_of::<u32>();
-        if actual_size < expected_size {
-            return Err(Err::Incomplete(Needed::new(expected_size)));
-        }
-
-        // Track the mandatory and optional fields to get the payload length
-        let mut payload_len = expected_size;
-        payload_len -= size_of::<u32>(); // header word
-        if header.t {
-            payload_len -= size_of::<u32>(); // trailer word
-        }
-
-        let (i, stream_id) = if matches!(
-            header.packet_type,
-            PktType::IfDataWithStream | PktType::ExtDataWithStream
-        ) {
-            let (i, stream_id) = be_u32(i)?;
-            payload_len -= size_of_val(&stream_id);
-            (i, Some(stream_id))
-        } else {
-            (i, None)
-        };
-
-        let (i, class_id) = if header.c {
-            let (i, class_id) = ClassId::parse(i)?;
-            payload_len -= size_of_val(&class_id);
-            (i, Some(class_id))
-        } else {
-            (i, None)
-        };
+        if packet.len() < expected_size {
+            return Err(Err::Incomplete(Needed::new(expected_size - packet.len())));
+        }
 
-        let (i, tsi) = if header.tsi == Tsi::None {
-            (i, None)
-        } else {
-            let (i, tsi) = be_u32(i)?;
-            payload_len -= size_of_val(&tsi);
-            (i, Some(tsi))
-        };
+        let payload_len = expected_size
+            .checked_sub(header.fields_len())
+            .ok_or(Err::Error(nom::error::Error::new(
+                packet,
+                ErrorKind::Verify,
+            )))?;
 
-        let (i, tsf) = if header.tsf == Tsf::None {
-            (i, None)
-        } else {
-            let (i, tsf) = be_u64(i)?;
-            payload_len -= size_of_val(&tsf);
-            (i, Some(tsf))
-        };
+        let (i, stream_id) = cond(header.packet_type.has_stream_id(), be_u32).parse(i)?;
+        let (i, class_id) = cond(header.c, ClassId::parse).parse(i)?;
+        let (i, tsi) = cond(header.tsi != Tsi::None, be_u32).parse(i)?;
+        let (i, tsf) = cond(header.tsf != Tsf::None, be_u64).parse(i)?;
+        let (i, data_payload) = take(payload_len)(i)?;
 
-        let (data_payload, i) = i.split_at(payload_len);
-
-        let (i, trailer) = if header.t {
-            let (i, trailer) = Trailer::parse(i)?;
-            (i, Some(trailer))
-        } else {
-            (i, None)
-        };
+        let (i, trailer) = cond(header.has_trailer(), Trailer::parse).parse(i)?;
 
         let packet = VrtPacket {
             header,
@@ -98,4 +77,119 @@ impl VrtPacket<'_> {
 
         Ok((i, packet))
     }
+
+    /// Parse the VRT packet at the start of `bytes`, returning it along with its length in bytes.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<(VrtPacket<'a>, usize), Error> {
+        let e = match VrtPacket::parse(bytes) {
+            Ok((rest, packet)) => return Ok((packet, bytes.len() - rest.len())),
+            Err(e @ Err::Incomplete(_)) => return Err(Error::from_nom(bytes, e, "header")),
+            Err(e) => e,
+        };
+
+        // The packet type and packet size are the only header fields that can be invalid.
+        let Ok((_, header)) = Header::parse(bytes) else {
+            let packet_type = bytes.first().map_or(0, |byte| byte >> 4);
+            return Err(Error::UnsupportedPacketType(packet_type));
+        };
+        let size = header.packet_size as usize * size_of::<u32>();
+        let min_size = header.fields_len();
+        if size < min_size {
+            return Err(Error::SizeMismatch { size, min_size });
+        }
+        Err(match Error::from_nom(bytes, e, "header") {
+            Error::InvalidField { offset, .. } => Error::InvalidField {
+                field: header.field_at(offset),
+                offset,
+            },
+            e => e,
+        })
+    }
+
+    /// Number of bytes needed to encode the VRT packet
+    pub fn encoded_len(&self) -> usize {
+        Header::SIZE
+            + self.stream_id.map_or(0, |v| size_of_val(&v))
+            + self.class_id.map_or(0, |_| ClassId::SIZE)
+            + self.tsi.map_or(0, |v| size_of_val(&v))
+            + self.tsf.map_or(0, |v| size_of_val(&v))
+            + self.payload.len()
+            + self.trailer.map_or(0, |_| Trailer::SIZE)
+    }
+
+    /// Encode the VRT packet into `buf` in VITA 49.0 word order, returning the number of bytes written.
+    ///
+    /// The header indicator bits must agree with the optional fields that are present, and
+    /// `header.packet_size` must match the encoded length in 32-bit words.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.check_header()?;
+
+        let len = self.encoded_len();
+        ensure_capacity(buf, len)?;
+
+        let mut pos = self.header.encode(buf)?;
+        if let Some(stream_id) = self.stream_id {
+            buf[pos..pos + 4].copy_from_slice(&stream_id.to_be_bytes());
+            pos += 4;
+        }
+        if let Some(class_id) = self.class_id {
+            pos += class_id.encode(&mut buf[pos..])?;
+        }
+        if let Some(tsi) = self.tsi {
+            buf[pos..pos + 4].copy_from_slice(&tsi.to_be_bytes());
+            pos += 4;
+        }
+        if let Some(tsf) = self.tsf {
+            buf[pos..pos + 8].copy_from_slice(&tsf.to_be_bytes());
+            pos += 8;
+        }
+        buf[pos..pos + self.payload.len()].copy_from_slice(self.payload);
+        pos += self.payload.len();
+        if let Some(trailer) = self.trailer {
+            pos += trailer.encode(&mut buf[pos..])?;
+        }
+
+        Ok(pos)
+    }
+
+    /// Encode the VRT packet into a newly allocated buffer.
+    #[cfg(feature = "alloc")]
+    pub fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        let mut buf = vec![0; self.encoded_len()];
+        self.encode(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Check that the header is consistent with the packet contents.
+    pub(crate) fn check_header(&self) -> Result<(), Error> {
+        let header = &self.header;
+        if header.packet_type.has_stream_id() != self.stream_id.is_some() {
+            return Err(Error::HeaderMismatch("stream_id"));
+        }
+        if header.c != self.class_id.is_some() {
+            return Err(Error::HeaderMismatch("class_id"));
+        }
+        if (header.tsi != Tsi::None) != self.tsi.is_some() {
+            return Err(Error::HeaderMismatch("tsi"));
+        }
+        if (header.tsf != Tsf::None) != self.tsf.is_some() {
+            return Err(Error::HeaderMismatch("tsf"));
+        }
+        if !header.indicators.matches(header.packet_type) {
+            return Err(Error::HeaderMismatch("indicators"));
+        }
+        if header.has_trailer() != self.trailer.is_some() {
+            return Err(Error::HeaderMismatch("trailer"));
+        }
+        if self.payload.len() % size_of::<u32>() != 0 {
+            return Err(Error::PayloadAlignment(self.payload.len()));
+        }
+        let words = self.encoded_len() / size_of::<u32>();
+        if words > u16::MAX as usize {
+            return Err(Error::PacketTooLarge(words));
+        }
+        if header.packet_size as usize != words {
+            return Err(Error::HeaderMismatch("packet_size"));
+        }
+        Ok(())
+    }
 }
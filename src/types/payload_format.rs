@@ -0,0 +1,432 @@
+use nom::{number::complete::be_u64, IResult, Parser};
+
+use super::mask;
+use crate::Error;
+
+/// Data Packet Payload Format
+///
+/// Describes how data items are packed in the payload of the paired data packet stream.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PayloadFormat {
+    /// Packing of item packing fields into 32-bit words.
+    pub packing_method: PackingMethod,
+    /// Real or complex sample type.
+    pub real_complex: RealComplexType,
+    /// Format of each data item.
+    pub data_item_format: DataItemFormat,
+    /// Do the sample components repeat, as opposed to the samples?
+    pub sample_component_repeat: bool,
+    /// Size of the event tag in bits (0-7).
+    pub event_tag_size: u8,
+    /// Size of the channel tag in bits (0-15).
+    pub channel_tag_size: u8,
+    /// Number of fractional bits in non-normalized fixed-point data items (0-15).
+    pub data_item_fraction_size: u8,
+    /// Size of each item packing field in bits (1-64).
+    pub item_packing_field_size: u8,
+    /// Size of each data item in bits (1-64).
+    pub data_item_size: u8,
+    /// Number of times the vector repeats (1-65536).
+    pub repeat_count: u32,
+    /// Number of samples in the vector (1-65536).
+    pub vector_size: u32,
+}
+
+impl PayloadFormat {
+    /// Parse the Data Packet Payload Format field
+    pub fn parse(i: &[u8]) -> IResult<&[u8], PayloadFormat> {
+        be_u64.map_res(PayloadFormat::try_from).parse(i)
+    }
+
+    /// Number of bits of each item packing field used by the data item and its tags.
+    fn used_bits(&self) -> u32 {
+        self.data_item_size as u32 + self.event_tag_size as u32 + self.channel_tag_size as u32
+    }
+
+    /// Convert the raw bits of a data item to an integer.
+    ///
+    /// Fixed-point data items convert to their integer value, saturating unsigned values above
+    /// `i64::MAX`; floating-point data items are truncated towards zero.
+    pub fn item_to_i64(&self, raw: u64) -> i64 {
+        let size = self.data_item_size as u32;
+        match self.data_item_format {
+            DataItemFormat::SignedFixed | DataItemFormat::SignedFixedNonNormalized => {
+                sign_extend(raw, size)
+            }
+            DataItemFormat::UnsignedFixed | DataItemFormat::UnsignedFixedNonNormalized => {
+                i64::try_from(raw).unwrap_or(i64::MAX)
+            }
+            _ => self.item_to_f64(raw) as i64,
+        }
+    }
+
+    /// Convert the raw bits of a data item to a floating-point value.
+    ///
+    /// Normalized fixed-point data items convert to the range [-1, 1) (signed) or [0, 1)
+    /// (unsigned). VRT floating-point data items hold a normalized fixed-point mantissa in the
+    /// upper bits and an unsigned exponent `e` in the lower bits, and convert to
+    /// `mantissa * 2^(e - (2^exponent_bits - 1))`.
+    pub fn item_to_f64(&self, raw: u64) -> f64 {
+        let size = self.data_item_size as i32;
+        let fraction_size = self.data_item_fraction_size as i32;
+        match self.data_item_format {
+            DataItemFormat::SignedFixed => sign_extend(raw, size as u32) as f64 * exp2(1 - size),
+            DataItemFormat::UnsignedFixed => raw as f64 * exp2(-size),
+            DataItemFormat::SignedFixedNonNormalized => {
+                sign_extend(raw, size as u32) as f64 * exp2(-fraction_size)
+            }
+            DataItemFormat::UnsignedFixedNonNormalized => raw as f64 * exp2(-fraction_size),
+            DataItemFormat::Ieee754Half => half_to_f32(raw as u16) as f64,
+            DataItemFormat::Ieee754Single => f32::from_bits(raw as u32) as f64,
+            DataItemFormat::Ieee754Double => f64::from_bits(raw),
+            DataItemFormat::SignedVrt(exponent_bits)
+            | DataItemFormat::UnsignedVrt(exponent_bits) => {
+                let exponent_bits = exponent_bits as i32;
+                let mantissa_size = size - exponent_bits;
+                let exponent = (raw & ((1 << exponent_bits) - 1)) as i32;
+                let mantissa = raw >> exponent_bits;
+                let mantissa = if self.data_item_format.is_signed() {
+                    sign_extend(mantissa, mantissa_size as u32) as f64 * exp2(1 - mantissa_size)
+                } else {
+                    mantissa as f64 * exp2(-mantissa_size)
+                };
+                mantissa * exp2(exponent - ((1 << exponent_bits) - 1))
+            }
+        }
+    }
+
+    /// Convert an integer to the raw bits of a data item, the inverse of [`item_to_i64`].
+    ///
+    /// Values outside the range of the data item saturate.
+    ///
+    /// [`item_to_i64`]: PayloadFormat::item_to_i64
+    pub fn item_from_i64(&self, value: i64) -> u64 {
+        let size = self.data_item_size as usize;
+        match self.data_item_format {
+            DataItemFormat::SignedFixed | DataItemFormat::SignedFixedNonNormalized => {
+                let max = mask(size.saturating_sub(1)) as i64;
+                (value.clamp(-max - 1, max) as u64) & mask(size)
+            }
+            DataItemFormat::UnsignedFixed | DataItemFormat::UnsignedFixedNonNormalized => {
+                (value.max(0) as u64).min(mask(size))
+            }
+            _ => self.item_from_f64(value as f64),
+        }
+    }
+
+    /// Convert a floating-point value to the raw bits of a data item, the inverse of
+    /// [`item_to_f64`], rounding to the nearest representable value.
+    ///
+    /// Values outside the range of the data item saturate.
+    ///
+    /// [`item_to_f64`]: PayloadFormat::item_to_f64
+    pub fn item_from_f64(&self, value: f64) -> u64 {
+        let size = self.data_item_size as i32;
+        let fraction_size = self.data_item_fraction_size as i32;
+        match self.data_item_format {
+            DataItemFormat::SignedFixed => signed_item(value * exp2(size - 1), size),
+            DataItemFormat::UnsignedFixed => unsigned_item(value * exp2(size), size),
+            DataItemFormat::SignedFixedNonNormalized => {
+                signed_item(value * exp2(fraction_size), size)
+            }
+            DataItemFormat::UnsignedFixedNonNormalized => {
+                unsigned_item(value * exp2(fraction_size), size)
+            }
+            DataItemFormat::Ieee754Half => f32_to_half(value as f32) as u64,
+            DataItemFormat::Ieee754Single => (value as f32).to_bits() as u64,
+            DataItemFormat::Ieee754Double => value.to_bits(),
+            DataItemFormat::SignedVrt(exponent_bits)
+            | DataItemFormat::UnsignedVrt(exponent_bits) => {
+                let exponent_bits = exponent_bits as i32;
+                let mantissa_size = size - exponent_bits;
+                let max_exponent = (1 << exponent_bits) - 1;
+                let signed = self.data_item_format.is_signed();
+                let fraction_bits = if signed {
+                    mantissa_size - 1
+                } else {
+                    mantissa_size
+                };
+                // Use the smallest exponent whose mantissa can hold the value, for the most precision.
+                let exponent = (0..max_exponent)
+                    .find(|&exponent| {
+                        let scaled = round(value * exp2(fraction_bits + max_exponent - exponent));
+                        if signed {
+                            sign_extend(signed_item(scaled, mantissa_size), mantissa_size as u32)
+                                == scaled as i64
+                        } else {
+                            unsigned_item(scaled, mantissa_size) as f64 == scaled
+                        }
+                    })
+                    .unwrap_or(max_exponent);
+                let scaled = value * exp2(fraction_bits + max_exponent - exponent);
+                let mantissa = if signed {
+                    signed_item(scaled, mantissa_size)
+                } else {
+                    unsigned_item(scaled, mantissa_size)
+                };
+                (mantissa << exponent_bits) | exponent as u64
+            }
+        }
+    }
+}
+
+impl TryFrom<u64> for PayloadFormat {
+    type Error = Error;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        let first_word = (value >> 32) as u32;
+        let second_word = value as u32;
+
+        let format = PayloadFormat {
+            packing_method: if (first_word >> 31) & 0x01 != 0 {
+                PackingMethod::LinkEfficient
+            } else {
+                PackingMethod::ProcessingEfficient
+            },
+            real_complex: RealComplexType::try_from(((first_word >> 29) & 0b11) as u8)?,
+            data_item_format: DataItemFormat::try_from(((first_word >> 24) & 0x1f) as u8)?,
+            sample_component_repeat: (first_word >> 23) & 0x01 != 0,
+            event_tag_size: ((first_word >> 20) & 0b111) as u8,
+            channel_tag_size: ((first_word >> 16) & 0xf) as u8,
+            data_item_fraction_size: ((first_word >> 12) & 0xf) as u8,
+            item_packing_field_size: ((first_word >> 6) & 0x3f) as u8 + 1,
+            data_item_size: (first_word & 0x3f) as u8 + 1,
+            repeat_count: (second_word >> 16) + 1,
+            vector_size: (second_word & 0xffff) + 1,
+        };
+
+        if format.used_bits() > format.item_packing_field_size as u32 {
+            return Err(Error::ItemPackingFieldSize(format.item_packing_field_size));
+        }
+
+        Ok(format)
+    }
+}
+
+impl From<PayloadFormat> for u64 {
+    fn from(format: PayloadFormat) -> u64 {
+        let first_word = ((format.packing_method as u32) << 31)
+            | ((u8::from(format.real_complex) as u32) << 29)
+            | ((u8::from(format.data_item_format) as u32) << 24)
+            | ((format.sample_component_repeat as u32) << 23)
+            | (((format.event_tag_size & 0b111) as u32) << 20)
+            | (((format.channel_tag_size & 0xf) as u32) << 16)
+            | (((format.data_item_fraction_size & 0xf) as u32) << 12)
+            | (((format.item_packing_field_size.wrapping_sub(1) & 0x3f) as u32) << 6)
+            | ((format.data_item_size.wrapping_sub(1) & 0x3f) as u32);
+        let second_word = ((format.repeat_count.wrapping_sub(1) & 0xffff) << 16)
+            | (format.vector_size.wrapping_sub(1) & 0xffff);
+
+        ((first_word as u64) << 32) | second_word as u64
+    }
+}
+
+/// Packing Method
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PackingMethod {
+    /// Item packing fields do not span 32-bit word boundaries.
+    ProcessingEfficient = 0,
+    /// Item packing fields are packed contiguously across 32-bit word boundaries.
+    LinkEfficient = 1,
+}
+
+/// Real/Complex Type
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RealComplexType {
+    /// Real samples.
+    Real,
+    /// Complex samples in Cartesian (I/Q) coordinates.
+    ComplexCartesian,
+    /// Complex samples in polar (magnitude/phase) coordinates.
+    ComplexPolar,
+}
+
+impl RealComplexType {
+    /// Number of data items in each sample.
+    pub fn components(self) -> usize {
+        match self {
+            RealComplexType::Real => 1,
+            RealComplexType::ComplexCartesian | RealComplexType::ComplexPolar => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for RealComplexType {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RealComplexType::Real),
+            1 => Ok(RealComplexType::ComplexCartesian),
+            2 => Ok(RealComplexType::ComplexPolar),
+            _ => Err(Error::RealComplexType(value)),
+        }
+    }
+}
+
+impl From<RealComplexType> for u8 {
+    fn from(real_complex: RealComplexType) -> u8 {
+        match real_complex {
+            RealComplexType::Real => 0,
+            RealComplexType::ComplexCartesian => 1,
+            RealComplexType::ComplexPolar => 2,
+        }
+    }
+}
+
+/// Data Item Format
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DataItemFormat {
+    /// Signed fixed-point, normalized to the range [-1, 1).
+    SignedFixed,
+    /// Signed VRT floating-point with the given number of exponent bits (1-6).
+    SignedVrt(u8),
+    /// Signed fixed-point with `data_item_fraction_size` fractional bits.
+    SignedFixedNonNormalized,
+    /// IEEE-754 half-precision floating-point.
+    Ieee754Half,
+    /// IEEE-754 single-precision floating-point.
+    Ieee754Single,
+    /// IEEE-754 double-precision floating-point.
+    Ieee754Double,
+    /// Unsigned fixed-point, normalized to the range [0, 1).
+    UnsignedFixed,
+    /// Unsigned VRT floating-point with the given number of exponent bits (1-6).
+    UnsignedVrt(u8),
+    /// Unsigned fixed-point with `data_item_fraction_size` fractional bits.
+    UnsignedFixedNonNormalized,
+}
+
+impl DataItemFormat {
+    /// Is the data item a signed (or floating-point) value?
+    pub fn is_signed(self) -> bool {
+        !matches!(
+            self,
+            DataItemFormat::UnsignedFixed
+                | DataItemFormat::UnsignedVrt(_)
+                | DataItemFormat::UnsignedFixedNonNormalized
+        )
+    }
+}
+
+impl TryFrom<u8> for DataItemFormat {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(DataItemFormat::SignedFixed),
+            0x01..=0x06 => Ok(DataItemFormat::SignedVrt(value)),
+            0x07 => Ok(DataItemFormat::SignedFixedNonNormalized),
+            0x0d => Ok(DataItemFormat::Ieee754Half),
+            0x0e => Ok(DataItemFormat::Ieee754Single),
+            0x0f => Ok(DataItemFormat::Ieee754Double),
+            0x10 => Ok(DataItemFormat::UnsignedFixed),
+            0x11..=0x16 => Ok(DataItemFormat::UnsignedVrt(value & 0xf)),
+            0x17 => Ok(DataItemFormat::UnsignedFixedNonNormalized),
+            _ => Err(Error::DataItemFormat(value)),
+        }
+    }
+}
+
+impl From<DataItemFormat> for u8 {
+    fn from(format: DataItemFormat) -> u8 {
+        match format {
+            DataItemFormat::SignedFixed => 0x00,
+            DataItemFormat::SignedVrt(exponent) => exponent & 0x7,
+            DataItemFormat::SignedFixedNonNormalized => 0x07,
+            DataItemFormat::Ieee754Half => 0x0d,
+            DataItemFormat::Ieee754Single => 0x0e,
+            DataItemFormat::Ieee754Double => 0x0f,
+            DataItemFormat::UnsignedFixed => 0x10,
+            DataItemFormat::UnsignedVrt(exponent) => 0x10 | (exponent & 0x7),
+            DataItemFormat::UnsignedFixedNonNormalized => 0x17,
+        }
+    }
+}
+
+/// Sign-extend the `bits` least-significant bits of `raw`.
+fn sign_extend(raw: u64, bits: u32) -> i64 {
+    let shift = 64 - bits.clamp(1, 64);
+    ((raw << shift) as i64) >> shift
+}
+
+/// Round half away from zero.
+fn round(value: f64) -> f64 {
+    // `as` truncates towards zero; values beyond the i64 range are already integral.
+    if value.abs() >= exp2(62) {
+        value
+    } else if value < 0.0 {
+        (value - 0.5) as i64 as f64
+    } else {
+        (value + 0.5) as i64 as f64
+    }
+}
+
+/// Round `value` into a `bits`-wide two's-complement data item, saturating.
+fn signed_item(value: f64, bits: i32) -> u64 {
+    let bits = bits.clamp(1, 64) as usize;
+    let max = mask(bits - 1) as i64;
+    ((round(value) as i64).clamp(-max - 1, max) as u64) & mask(bits)
+}
+
+/// Round `value` into a `bits`-wide unsigned data item, saturating.
+fn unsigned_item(value: f64, bits: i32) -> u64 {
+    (round(value) as u64).min(mask(bits.clamp(0, 64) as usize))
+}
+
+/// `2^n` for exponents within the range of normal `f64` values.
+fn exp2(n: i32) -> f64 {
+    f64::from_bits(((n + 1023) as u64) << 52)
+}
+
+/// Convert an IEEE-754 half-precision value to single precision.
+fn half_to_f32(half: u16) -> f32 {
+    let negative = half & 0x8000 != 0;
+    let sign = (negative as u32) << 31;
+    let exponent = ((half >> 10) & 0x1f) as u32;
+    let mantissa = (half & 0x3ff) as u32;
+
+    match exponent {
+        0 => {
+            // Zero or subnormal: mantissa * 2^-24
+            let magnitude = mantissa as f32 * exp2(-24) as f32;
+            if negative {
+                -magnitude
+            } else {
+                magnitude
+            }
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 127 - 15) << 23) | (mantissa << 13)),
+    }
+}
+
+/// Convert a single-precision value to IEEE-754 half precision, rounding to nearest.
+fn f32_to_half(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        // Infinity or NaN
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if half_exponent <= 0 {
+        // Subnormal or zero
+        if half_exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        let round = (mantissa >> (shift - 1)) & 0x01;
+        return sign | ((mantissa >> shift) + round) as u16;
+    }
+    // A carry out of the mantissa correctly increments the exponent.
+    let round = (mantissa >> 12) & 0x01;
+    sign | ((((half_exponent as u32) << 10) | (mantissa >> 13)) + round) as u16
+}
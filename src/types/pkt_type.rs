@@ -15,6 +15,35 @@ pub enum PktType {
     IfContext,
     /// Custom Extension packet with a context packet stream
     ExtContext,
+    /// Standard Command packet with a command packet stream
+    Command,
+    /// Custom Extension Command packet with a command packet stream
+    ExtCommand,
+}
+
+impl PktType {
+    /// Does this packet type carry a Stream Identifier?
+    pub fn has_stream_id(self) -> bool {
+        matches!(
+            self,
+            PktType::IfDataWithStream
+                | PktType::ExtDataWithStream
+                | PktType::IfContext
+                | PktType::ExtContext
+                | PktType::Command
+                | PktType::ExtCommand
+        )
+    }
+
+    /// Is this a context packet type?
+    pub fn is_context(self) -> bool {
+        matches!(self, PktType::IfContext | PktType::ExtContext)
+    }
+
+    /// Is this a command packet type?
+    pub fn is_command(self) -> bool {
+        matches!(self, PktType::Command | PktType::ExtCommand)
+    }
 }
 
 impl TryFrom<u8> for PktType {
@@ -28,6 +57,8 @@ impl TryFrom<u8> for PktType {
             3 => Ok(PktType::ExtDataWithStream),
             4 => Ok(PktType::IfContext),
             5 => Ok(PktType::ExtContext),
+            6 => Ok(PktType::Command),
+            7 => Ok(PktType::ExtCommand),
             _ => Err(Error::PktType(value)),
         }
     }
@@ -42,6 +73,8 @@ impl From<PktType> for u8 {
             PktType::ExtDataWithStream => 3,
             PktType::IfContext => 4,
             PktType::ExtContext => 5,
+            PktType::Command => 6,
+            PktType::ExtCommand => 7,
         }
     }
 }
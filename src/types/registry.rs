@@ -0,0 +1,102 @@
+use alloc::{boxed::Box, collections::BTreeMap};
+use core::fmt;
+
+use super::*;
+use crate::Error;
+
+/// Decoder of the payload of one class of packets into a typed message.
+///
+/// Decoders are `Send + Sync` so that a [`ClassRegistry`] can be shared between threads.
+pub trait ClassDecoder: Send + Sync {
+    /// Message decoded from a packet.
+    type Message;
+
+    /// Decode `packet`, whose Class ID matches the class the decoder is registered for.
+    fn decode(&self, packet: &VrtPacket<'_>) -> Result<Self::Message, Error>;
+}
+
+/// Registry of [`ClassDecoder`]s, keyed by the OUI, Information Class Code and Packet Class Code
+/// of the Class ID.
+///
+/// Every decoder produces the same message type `M`, typically an application enum with one
+/// variant per vendor-specific packet class.
+pub struct ClassRegistry<M> {
+    decoders: BTreeMap<(Oui, u16, u16), Box<dyn ClassDecoder<Message = M>>>,
+}
+
+impl<M> ClassRegistry<M> {
+    /// Registry without any decoders.
+    pub fn new() -> Self {
+        ClassRegistry {
+            decoders: BTreeMap::new(),
+        }
+    }
+
+    /// Decode packets of the given class with `decoder`, returning the decoder it replaces.
+    pub fn register(
+        &mut self,
+        oui: Oui,
+        information_class_code: u16,
+        packet_class_code: u16,
+        decoder: impl ClassDecoder<Message = M> + 'static,
+    ) -> Option<Box<dyn ClassDecoder<Message = M>>> {
+        self.decoders.insert(
+            (oui, information_class_code, packet_class_code),
+            Box::new(decoder),
+        )
+    }
+
+    /// Remove the decoder of the given class, returning it.
+    pub fn unregister(
+        &mut self,
+        oui: Oui,
+        information_class_code: u16,
+        packet_class_code: u16,
+    ) -> Option<Box<dyn ClassDecoder<Message = M>>> {
+        self.decoders
+            .remove(&(oui, information_class_code, packet_class_code))
+    }
+
+    /// Decoder registered for the class of `class_id`.
+    pub fn decoder(&self, class_id: &ClassId) -> Option<&dyn ClassDecoder<Message = M>> {
+        self.decoders
+            .get(&(
+                class_id.oui,
+                class_id.information_class_code,
+                class_id.packet_class_code,
+            ))
+            .map(|decoder| decoder.as_ref())
+    }
+
+    /// Decode `packet` with the decoder registered for its Class ID.
+    ///
+    /// Returns `None` if the packet has no Class ID or no decoder is registered for its class.
+    pub fn decode(&self, packet: &VrtPacket<'_>) -> Option<Result<M, Error>> {
+        let decoder = self.decoder(packet.class_id.as_ref()?)?;
+        Some(decoder.decode(packet))
+    }
+
+    /// Number of registered decoders.
+    pub fn len(&self) -> usize {
+        self.decoders.len()
+    }
+
+    /// Are no decoders registered?
+    pub fn is_empty(&self) -> bool {
+        self.decoders.is_empty()
+    }
+}
+
+impl<M> Default for ClassRegistry<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M> fmt::Debug for ClassRegistry<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClassRegistry")
+            .field("classes", &self.decoders.keys())
+            .finish()
+    }
+}
@@ -0,0 +1,495 @@
+use core::marker::PhantomData;
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use super::*;
+use crate::Error;
+
+/// A data item and its tags, unpacked from an item packing field.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DataItem {
+    /// Raw bits of the data item, right-justified.
+    pub value: u64,
+    /// Event tag bits.
+    pub event_tag: u8,
+    /// Channel tag bits.
+    pub channel_tag: u16,
+}
+
+/// Iterator over the [`DataItem`]s in a data payload.
+#[derive(Clone, Debug)]
+pub struct DataItems<'a> {
+    format: PayloadFormat,
+    payload: &'a [u8],
+    bit: usize,
+    end: usize,
+    index: usize,
+    vector_size: usize,
+}
+
+impl<'a> DataItems<'a> {
+    /// Iterate over the data items of `payload` packed according to `format`.
+    pub fn new(format: PayloadFormat, payload: &'a [u8]) -> Self {
+        DataItems {
+            format,
+            payload,
+            bit: 0,
+            end: payload.len() * 8,
+            index: 0,
+            vector_size: 0,
+        }
+    }
+
+    /// Ignore the last `pad_bits` bits of the payload, which hold no data.
+    pub fn with_pad_bits(mut self, pad_bits: u8) -> Self {
+        self.end = (self.payload.len() * 8).saturating_sub(pad_bits as usize);
+        self
+    }
+
+    /// Payload format used to unpack the data items.
+    pub fn format(&self) -> &PayloadFormat {
+        &self.format
+    }
+
+    /// Yield the data items in sample order, undoing the sample-component repeat of the format.
+    fn in_sample_order(mut self) -> Self {
+        self.vector_size = self.format.component_repeat_vector_size();
+        self
+    }
+}
+
+impl Iterator for DataItems<'_> {
+    type Item = DataItem;
+
+    fn next(&mut self) -> Option<DataItem> {
+        let field_size = self.format.item_packing_field_size as usize;
+        if field_size == 0 {
+            return None;
+        }
+        let start = if self.vector_size == 0 {
+            self.format.field_start(self.bit)
+        } else {
+            let items = self.format.item_count(self.end) & !1;
+            if self.index >= items {
+                return None;
+            }
+            let index = component_repeat_index(self.index, items, self.vector_size);
+            self.format.field_offset(index)
+        };
+        if start + field_size > self.end {
+            return None;
+        }
+
+        let field = read_bits(self.payload, start, field_size);
+        self.bit = start + field_size;
+        self.index += 1;
+
+        let data_item_size = self.format.data_item_size as usize;
+        let channel_tag_size = self.format.channel_tag_size as usize;
+        let event_tag_size = self.format.event_tag_size as usize;
+
+        Some(DataItem {
+            value: (field >> field_size.saturating_sub(data_item_size)) & mask(data_item_size),
+            event_tag: ((field >> channel_tag_size) & mask(event_tag_size)) as u8,
+            channel_tag: (field & mask(channel_tag_size)) as u16,
+        })
+    }
+}
+
+/// Writer that packs [`DataItem`]s into a data payload.
+#[derive(Debug)]
+pub struct DataItemWriter<'a> {
+    format: PayloadFormat,
+    buf: &'a mut [u8],
+    bit: usize,
+    index: usize,
+    items: usize,
+    vector_size: usize,
+}
+
+impl<'a> DataItemWriter<'a> {
+    /// Pack data items into `buf` according to `format`. `buf` is expected to be zeroed.
+    pub fn new(format: PayloadFormat, buf: &'a mut [u8]) -> Self {
+        DataItemWriter {
+            format,
+            buf,
+            bit: 0,
+            index: 0,
+            items: 0,
+            vector_size: 0,
+        }
+    }
+
+    /// Payload format used to pack the data items.
+    pub fn format(&self) -> &PayloadFormat {
+        &self.format
+    }
+
+    /// Take `items` data items in sample order and lay them out with the sample-component repeat
+    /// of the format.
+    fn in_sample_order(mut self, items: usize) -> Self {
+        self.items = items & !1;
+        self.vector_size = self.format.component_repeat_vector_size();
+        self
+    }
+
+    /// Pack the next data item.
+    pub fn push(&mut self, item: DataItem) -> Result<(), Error> {
+        let field_size = self.format.item_packing_field_size as usize;
+        let data_item_size = self.format.data_item_size as usize;
+        let channel_tag_size = self.format.channel_tag_size as usize;
+        let event_tag_size = self.format.event_tag_size as usize;
+
+        let start = if self.vector_size != 0 && self.index < self.items {
+            let index = component_repeat_index(self.index, self.items, self.vector_size);
+            self.format.field_offset(index)
+        } else {
+            self.format.field_start(self.bit)
+        };
+        let needed = (start + field_size).div_ceil(8);
+        ensure_capacity(self.buf, needed)?;
+
+        let field = ((item.value & mask(data_item_size))
+            << field_size.saturating_sub(data_item_size))
+            | ((item.event_tag as u64 & mask(event_tag_size)) << channel_tag_size)
+            | (item.channel_tag as u64 & mask(channel_tag_size));
+        write_bits(self.buf, start, field_size, field);
+        self.bit = self.bit.max(start + field_size);
+        self.index += 1;
+
+        Ok(())
+    }
+
+    /// Pack the next data item without tags.
+    pub fn push_value(&mut self, value: u64) -> Result<(), Error> {
+        self.push(DataItem {
+            value,
+            ..DataItem::default()
+        })
+    }
+
+    /// Number of bytes written, rounded up to a whole number of 32-bit words.
+    pub fn len(&self) -> usize {
+        self.bit.div_ceil(32) * size_of::<u32>()
+    }
+
+    /// Has nothing been written yet?
+    pub fn is_empty(&self) -> bool {
+        self.bit == 0
+    }
+}
+
+/// Index of the item packing field holding data item `index`, in sample order, of a payload of
+/// `items` complex data items whose sample components repeat within each vector of `vector_size`
+/// samples.
+///
+/// Each vector holds the real components of its samples followed by their imaginary components.
+/// The last vector may be shorter.
+fn component_repeat_index(index: usize, items: usize, vector_size: usize) -> usize {
+    let vector_items = 2 * vector_size;
+    let start = index - index % vector_items;
+    let samples = (items - start).min(vector_items) / 2;
+    let offset = index - start;
+    start + offset % 2 * samples + offset / 2
+}
+
+/// Write the `len` (at most 64) least-significant bits of `value` starting `offset` bits into `data`.
+fn write_bits(data: &mut [u8], offset: usize, len: usize, value: u64) {
+    let start = offset / 8;
+    let end = (offset + len).div_ceil(8);
+    let shift = end * 8 - offset - len;
+    let window = data[start..end]
+        .iter()
+        .fold(0u128, |acc, &byte| (acc << 8) | byte as u128);
+    let field_mask = (mask(len) as u128) << shift;
+    let mut window = (window & !field_mask) | (((value & mask(len)) as u128) << shift);
+
+    for byte in data[start..end].iter_mut().rev() {
+        *byte = window as u8;
+        window >>= 8;
+    }
+}
+
+/// Read `len` (at most 64) bits starting `offset` bits into `data`, most-significant bit first.
+fn read_bits(data: &[u8], offset: usize, len: usize) -> u64 {
+    let start = offset / 8;
+    let end = (offset + len).div_ceil(8);
+    let window = data[start..end]
+        .iter()
+        .fold(0u128, |acc, &byte| (acc << 8) | byte as u128);
+
+    ((window >> (end * 8 - offset - len)) as u64) & mask(len)
+}
+
+/// Mask of the `len` least-significant bits.
+pub(crate) fn mask(len: usize) -> u64 {
+    if len >= 64 {
+        u64::MAX
+    } else {
+        (1 << len) - 1
+    }
+}
+
+/// A sample type that can be packed into and unpacked from a data payload.
+pub trait Sample: Sized {
+    /// Number of data items in each sample.
+    const ITEMS: usize;
+
+    /// Unpack the next sample from `items`.
+    fn unpack(items: &mut DataItems<'_>) -> Option<Self>;
+
+    /// Pack the sample into `writer`.
+    fn pack(&self, writer: &mut DataItemWriter<'_>) -> Result<(), Error>;
+}
+
+macro_rules! integer_sample {
+    ($($ty:ty),*) => {
+        $(
+            /// Fixed-point data items convert to their integer value, saturating if it does not fit.
+            impl Sample for $ty {
+                const ITEMS: usize = 1;
+
+                fn unpack(items: &mut DataItems<'_>) -> Option<Self> {
+                    let item = items.next()?;
+                    let value = items.format().item_to_i64(item.value);
+                    Some(<$ty>::try_from(value).unwrap_or(if value < 0 { <$ty>::MIN } else { <$ty>::MAX }))
+                }
+
+                fn pack(&self, writer: &mut DataItemWriter<'_>) -> Result<(), Error> {
+                    let value = writer.format().item_from_i64(*self as i64);
+                    writer.push_value(value)
+                }
+            }
+        )*
+    };
+}
+
+integer_sample!(i8, i16, i32, i64);
+
+impl Sample for f32 {
+    const ITEMS: usize = 1;
+
+    fn unpack(items: &mut DataItems<'_>) -> Option<Self> {
+        let item = items.next()?;
+        Some(items.format().item_to_f64(item.value) as f32)
+    }
+
+    fn pack(&self, writer: &mut DataItemWriter<'_>) -> Result<(), Error> {
+        let value = writer.format().item_from_f64(*self as f64);
+        writer.push_value(value)
+    }
+}
+
+impl Sample for f64 {
+    const ITEMS: usize = 1;
+
+    fn unpack(items: &mut DataItems<'_>) -> Option<Self> {
+        let item = items.next()?;
+        Some(items.format().item_to_f64(item.value))
+    }
+
+    fn pack(&self, writer: &mut DataItemWriter<'_>) -> Result<(), Error> {
+        let value = writer.format().item_from_f64(*self);
+        writer.push_value(value)
+    }
+}
+
+/// Complex sample
+///
+/// For [`RealComplexType::ComplexPolar`] payloads `re` holds the magnitude and `im` the phase.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Complex<T> {
+    /// Real (in-phase) component.
+    pub re: T,
+    /// Imaginary (quadrature) component.
+    pub im: T,
+}
+
+impl<T: Sample> Sample for Complex<T> {
+    const ITEMS: usize = 2 * T::ITEMS;
+
+    fn unpack(items: &mut DataItems<'_>) -> Option<Self> {
+        let re = T::unpack(items)?;
+        let im = T::unpack(items)?;
+        Some(Complex { re, im })
+    }
+
+    fn pack(&self, writer: &mut DataItemWriter<'_>) -> Result<(), Error> {
+        self.re.pack(writer)?;
+        self.im.pack(writer)
+    }
+}
+
+/// Iterator over the samples in a data payload.
+#[derive(Clone, Debug)]
+pub struct Samples<'a, T> {
+    items: DataItems<'a>,
+    sample: PhantomData<T>,
+}
+
+impl<T: Sample> Iterator for Samples<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        T::unpack(&mut self.items)
+    }
+}
+
+impl PayloadFormat {
+    /// Bit offset at which the item packing field following `bit` starts.
+    fn field_start(&self, bit: usize) -> usize {
+        let field_size = self.item_packing_field_size as usize;
+        let word_bit = bit % 32;
+        if self.packing_method == PackingMethod::ProcessingEfficient
+            && word_bit != 0
+            && word_bit + field_size > 32
+        {
+            // Item packing fields never straddle a 32-bit word boundary.
+            bit + 32 - word_bit
+        } else {
+            bit
+        }
+    }
+
+    /// Bit offset of item packing field `index`.
+    fn field_offset(&self, index: usize) -> usize {
+        let field_size = self.item_packing_field_size as usize;
+        match self.packing_method {
+            PackingMethod::LinkEfficient => index * field_size,
+            PackingMethod::ProcessingEfficient if field_size <= 32 => {
+                let per_word = 32 / field_size;
+                index / per_word * 32 + index % per_word * field_size
+            }
+            PackingMethod::ProcessingEfficient => index * field_size.div_ceil(32) * 32,
+        }
+    }
+
+    /// Number of samples in each vector whose components repeat, or 0 if the samples are not
+    /// complex or their components do not repeat.
+    fn component_repeat_vector_size(&self) -> usize {
+        if self.sample_component_repeat && self.real_complex != RealComplexType::Real {
+            self.vector_size as usize
+        } else {
+            0
+        }
+    }
+
+    /// Number of bytes needed to pack `items` data items, padded to a whole number of 32-bit words.
+    pub fn packed_len(&self, items: usize) -> usize {
+        let field_size = (self.item_packing_field_size as usize).max(1);
+        let words = match self.packing_method {
+            PackingMethod::LinkEfficient => (items * field_size).div_ceil(32),
+            PackingMethod::ProcessingEfficient if field_size <= 32 => {
+                items.div_ceil(32 / field_size)
+            }
+            PackingMethod::ProcessingEfficient => items * field_size.div_ceil(32),
+        };
+        words * size_of::<u32>()
+    }
+
+    /// Number of whole item packing fields in the first `bits` bits of a payload.
+    pub fn item_count(&self, bits: usize) -> usize {
+        let field_size = self.item_packing_field_size as usize;
+        if field_size == 0 {
+            return 0;
+        }
+        match self.packing_method {
+            PackingMethod::LinkEfficient => bits / field_size,
+            PackingMethod::ProcessingEfficient if field_size <= 32 => {
+                bits / 32 * (32 / field_size) + bits % 32 / field_size
+            }
+            PackingMethod::ProcessingEfficient => {
+                let stride = field_size.div_ceil(32) * 32;
+                (bits + stride - field_size) / stride
+            }
+        }
+    }
+
+    /// Pack `items` into `buf`, returning the number of bytes written.
+    ///
+    /// The payload is padded with zero bits to a whole number of 32-bit words.
+    pub fn pack_items(&self, items: &[DataItem], buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.packed_len(items.len());
+        ensure_capacity(buf, len)?;
+        buf[..len].fill(0);
+
+        let mut writer = DataItemWriter::new(*self, &mut buf[..len]);
+        for item in items {
+            writer.push(*item)?;
+        }
+        Ok(len)
+    }
+
+    /// Pack `samples` into `buf`, returning the number of bytes written.
+    ///
+    /// Complex samples whose components repeat are laid out with the real components of each
+    /// vector followed by its imaginary components. The payload is padded with zero bits to a
+    /// whole number of 32-bit words.
+    pub fn pack<T: Sample>(&self, samples: &[T], buf: &mut [u8]) -> Result<usize, Error> {
+        let items = samples.len() * T::ITEMS;
+        let len = self.packed_len(items);
+        ensure_capacity(buf, len)?;
+        buf[..len].fill(0);
+
+        let mut writer = DataItemWriter::new(*self, &mut buf[..len]).in_sample_order(items);
+        for sample in samples {
+            sample.pack(&mut writer)?;
+        }
+        Ok(len)
+    }
+
+    /// Pack `samples` into a newly allocated payload.
+    #[cfg(feature = "alloc")]
+    pub fn pack_to_vec<T: Sample>(&self, samples: &[T]) -> Result<Vec<u8>, Error> {
+        let mut buf = vec![0; self.packed_len(samples.len() * T::ITEMS)];
+        self.pack(samples, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Iterate over the data items of `payload`.
+    pub fn items<'a>(&self, payload: &'a [u8]) -> DataItems<'a> {
+        DataItems::new(*self, payload)
+    }
+
+    /// Iterate over the samples of `payload`, converted to `T`.
+    ///
+    /// The components of complex samples are paired up across each vector when they repeat.
+    pub fn samples<'a, T: Sample>(&self, payload: &'a [u8]) -> Samples<'a, T> {
+        Samples {
+            items: self.items(payload).in_sample_order(),
+            sample: PhantomData,
+        }
+    }
+}
+
+impl<'a> VrtPacket<'a> {
+    /// Number of pad bits at the end of the data payload, from the Class ID.
+    pub fn pad_bit_count(&self) -> u8 {
+        self.class_id.map_or(0, |class_id| class_id.pad_bit_count)
+    }
+
+    /// Number of payload bits that hold data, excluding the pad bits.
+    pub fn payload_bits(&self) -> usize {
+        (self.payload.len() * 8).saturating_sub(self.pad_bit_count() as usize)
+    }
+
+    /// Number of whole samples of type `T` in the data payload packed according to `format`.
+    pub fn sample_count<T: Sample>(&self, format: &PayloadFormat) -> usize {
+        format.item_count(self.payload_bits()) / T::ITEMS
+    }
+
+    /// Iterate over the samples of the data payload packed according to `format`, converted to `T`.
+    ///
+    /// The pad bits at the end of the payload are ignored, and the components of complex samples
+    /// are paired up across each vector when they repeat.
+    pub fn samples<T: Sample>(&self, format: &PayloadFormat) -> Samples<'a, T> {
+        Samples {
+            items: format
+                .items(self.payload)
+                .with_pad_bits(self.pad_bit_count())
+                .in_sample_order(),
+            sample: PhantomData,
+        }
+    }
+}
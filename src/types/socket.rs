@@ -0,0 +1,199 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket},
+};
+
+use socket2::SockRef;
+
+use super::*;
+use crate::Error;
+
+/// Blocking UDP socket that receives and sends VRT packets.
+///
+/// Each datagram may carry several VRT packets, which are received in order. A packet that
+/// fails to parse yields an error and the rest of its datagram is dropped.
+///
+/// ```no_run
+/// use std::net::Ipv4Addr;
+///
+/// use vrt::VrtSocket;
+///
+/// # fn run() -> Result<(), vrt::Error> {
+/// let mut socket = VrtSocket::bind("0.0.0.0:4991")?;
+/// socket.join_multicast_v4(&Ipv4Addr::new(239, 1, 2, 3), &Ipv4Addr::UNSPECIFIED)?;
+/// loop {
+///     let (packet, source) = socket.recv_packet()?;
+///     println!("{:?} from {}", packet.stream_id, source);
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct VrtSocket {
+    socket: UdpSocket,
+    datagram: DatagramBuffer,
+    source: Option<SocketAddr>,
+    send_buf: Vec<u8>,
+}
+
+impl VrtSocket {
+    /// Default size of the datagram buffer, large enough for any UDP datagram.
+    pub const DEFAULT_BUFFER_SIZE: usize = DatagramBuffer::DEFAULT_SIZE;
+
+    /// Receive and send packets on `socket`.
+    pub fn new(socket: UdpSocket) -> Self {
+        VrtSocket {
+            socket,
+            datagram: DatagramBuffer::new(Self::DEFAULT_BUFFER_SIZE),
+            source: None,
+            send_buf: Vec::new(),
+        }
+    }
+
+    /// Bind a new UDP socket to `addr`.
+    pub fn bind(addr: impl ToSocketAddrs) -> Result<Self, Error> {
+        Ok(Self::new(UdpSocket::bind(addr)?))
+    }
+
+    /// Receive datagrams into a buffer of `size` bytes; longer datagrams are truncated.
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        self.datagram = DatagramBuffer::new(size);
+        self
+    }
+
+    /// Set the socket receive buffer size (`SO_RCVBUF`) in bytes.
+    ///
+    /// A larger buffer absorbs bursts of packets that arrive while the socket is not read.
+    pub fn set_recv_buffer_size(&self, size: usize) -> Result<(), Error> {
+        Ok(SockRef::from(&self.socket).set_recv_buffer_size(size)?)
+    }
+
+    /// Socket receive buffer size (`SO_RCVBUF`) in bytes.
+    pub fn recv_buffer_size(&self) -> Result<usize, Error> {
+        Ok(SockRef::from(&self.socket).recv_buffer_size()?)
+    }
+
+    /// Join the IPv4 multicast group `group` on the interface with address `interface`.
+    pub fn join_multicast_v4(&self, group: &Ipv4Addr, interface: &Ipv4Addr) -> Result<(), Error> {
+        Ok(self.socket.join_multicast_v4(group, interface)?)
+    }
+
+    /// Leave the IPv4 multicast group `group` on the interface with address `interface`.
+    pub fn leave_multicast_v4(&self, group: &Ipv4Addr, interface: &Ipv4Addr) -> Result<(), Error> {
+        Ok(self.socket.leave_multicast_v4(group, interface)?)
+    }
+
+    /// Join the IPv6 multicast group `group` on the interface with index `interface`.
+    pub fn join_multicast_v6(&self, group: &Ipv6Addr, interface: u32) -> Result<(), Error> {
+        Ok(self.socket.join_multicast_v6(group, interface)?)
+    }
+
+    /// Leave the IPv6 multicast group `group` on the interface with index `interface`.
+    pub fn leave_multicast_v6(&self, group: &Ipv6Addr, interface: u32) -> Result<(), Error> {
+        Ok(self.socket.leave_multicast_v6(group, interface)?)
+    }
+
+    /// Address that sent the most recently received packet.
+    pub fn source_addr(&self) -> Option<SocketAddr> {
+        self.source
+    }
+
+    /// Borrow the underlying socket, for example to set a read timeout.
+    pub fn get_ref(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Return the underlying socket, dropping any packets not yet received.
+    pub fn into_inner(self) -> UdpSocket {
+        self.socket
+    }
+
+    /// Receive the next packet along with the address that sent it.
+    ///
+    /// The packet borrows the receive buffer until the next call.
+    pub fn recv_packet(&mut self) -> Result<(VrtPacket<'_>, SocketAddr), Error> {
+        let source = match self.source {
+            Some(source) if !self.datagram.is_empty() => source,
+            _ => loop {
+                let (len, source) = self.socket.recv_from(self.datagram.recv_buf())?;
+                self.datagram.filled(len);
+                self.source = Some(source);
+                if len > 0 {
+                    break source;
+                }
+            },
+        };
+        Ok((self.datagram.next_packet()?, source))
+    }
+
+    /// Encode `packet` and send it to `target` as a single datagram.
+    pub fn send_packet(
+        &mut self,
+        packet: &VrtPacket<'_>,
+        target: impl ToSocketAddrs,
+    ) -> Result<(), Error> {
+        self.send_buf.resize(packet.encoded_len(), 0);
+        packet.encode(&mut self.send_buf)?;
+        let len = self.socket.send_to(&self.send_buf, target)?;
+        check_sent(len, self.send_buf.len())
+    }
+}
+
+/// Buffer holding a received datagram and the part of it not yet parsed.
+#[derive(Debug)]
+pub(crate) struct DatagramBuffer {
+    buf: Vec<u8>,
+    remaining: Range<usize>,
+}
+
+impl DatagramBuffer {
+    /// Default size, large enough for any UDP datagram.
+    pub(crate) const DEFAULT_SIZE: usize = 65_535;
+
+    pub(crate) fn new(size: usize) -> Self {
+        DatagramBuffer {
+            buf: vec![0; size],
+            remaining: 0..0,
+        }
+    }
+
+    /// Has the whole datagram been parsed?
+    pub(crate) fn is_empty(&self) -> bool {
+        self.remaining.is_empty()
+    }
+
+    /// Buffer to receive the next datagram into, dropping the current one.
+    pub(crate) fn recv_buf(&mut self) -> &mut [u8] {
+        self.remaining = 0..0;
+        &mut self.buf
+    }
+
+    /// Record that a datagram of `len` bytes was received.
+    pub(crate) fn filled(&mut self, len: usize) {
+        self.remaining = 0..len;
+    }
+
+    /// Parse the next packet of the datagram, dropping the rest of it on failure.
+    pub(crate) fn next_packet(&mut self) -> Result<VrtPacket<'_>, Error> {
+        let datagram = &self.buf[self.remaining.clone()];
+        match VrtPacket::from_bytes(datagram) {
+            Ok((packet, len)) => {
+                self.remaining.start += len;
+                Ok(packet)
+            }
+            Err(e) => {
+                self.remaining = 0..0;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Fail unless the whole packet was sent as a single datagram.
+pub(crate) fn check_sent(sent: usize, len: usize) -> Result<(), Error> {
+    if sent != len {
+        return Err(io::Error::from(io::ErrorKind::WriteZero).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use alloc::vec::Vec;
+
+use nom::Err;
+
+use super::*;
+use crate::Error;
+
+/// Reassembles [`VrtPacket`]s from a byte stream, such as a TCP connection, whose reads do not
+/// line up with packet boundaries.
+///
+/// ```
+/// use vrt::{PktType, VrtPacketBuilder, VrtStreamDecoder};
+///
+/// let packet = VrtPacketBuilder::new(PktType::IfData)
+///     .payload(&[1, 2, 3, 4])
+///     .build()
+///     .unwrap();
+/// let bytes = packet.encode_to_vec().unwrap();
+///
+/// let mut decoder = VrtStreamDecoder::new();
+/// decoder.push(&bytes[..3]);
+/// assert_eq!(decoder.decode(), Ok(None));
+/// decoder.push(&bytes[3..]);
+/// assert_eq!(decoder.decode(), Ok(Some(packet)));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct VrtStreamDecoder {
+    buf: Vec<u8>,
+    start: usize,
+}
+
+impl VrtStreamDecoder {
+    /// Create an empty decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty decoder that can buffer `capacity` bytes without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        VrtStreamDecoder {
+            buf: Vec::with_capacity(capacity),
+            start: 0,
+        }
+    }
+
+    /// Append bytes received from the stream.
+    pub fn push(&mut self, data: &[u8]) {
+        // Drop the packets already decoded before growing the buffer.
+        if self.start > 0 {
+            self.buf.drain(..self.start);
+            self.start = 0;
+        }
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Decode the next complete packet, or `None` if more data is needed.
+    ///
+    /// A packet that fails to parse is discarded using the size in its header. When the header
+    /// itself is invalid, all buffered data is discarded since packet boundaries are lost.
+    pub fn decode(&mut self) -> Result<Option<VrtPacket<'_>>, Error> {
+        let start = self.start;
+        let len = match Header::parse(&self.buf[start..]) {
+            Ok((_, header)) if header.packet_size > 0 => {
+                header.packet_size as usize * size_of::<u32>()
+            }
+            Ok((_, header)) => {
+                self.start = self.buf.len();
+                return Err(Error::SizeMismatch {
+                    size: 0,
+                    min_size: header.fields_len(),
+                });
+            }
+            Err(Err::Incomplete(_)) => return Ok(None),
+            Err(_) => {
+                self.start = self.buf.len();
+                return Err(Error::UnsupportedPacketType(self.buf[start] >> 4));
+            }
+        };
+        if self.buf.len() - start < len {
+            return Ok(None);
+        }
+
+        self.start += len;
+        let (packet, _) = VrtPacket::from_bytes(&self.buf[start..start + len])?;
+        Ok(Some(packet))
+    }
+
+    /// Number of buffered bytes not yet decoded.
+    pub fn len(&self) -> usize {
+        self.buf.len() - self.start
+    }
+
+    /// Are there no buffered bytes left to decode?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Discard all buffered bytes, for example after reconnecting.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.start = 0;
+    }
+}
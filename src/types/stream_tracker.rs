@@ -0,0 +1,271 @@
+use alloc::collections::BTreeMap;
+
+use super::*;
+use crate::Error;
+
+/// Latest context of a stream, accumulated from its context packets.
+///
+/// Fields absent from a context packet keep the value from earlier packets.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StreamContext {
+    /// Class Id of the latest context packet
+    pub class_id: Option<ClassId>,
+    /// Timestamp of the latest context packet
+    pub timestamp: Option<Timestamp>,
+    /// Reference Point Identifier
+    pub reference_point_id: Option<u32>,
+    /// Bandwidth
+    pub bandwidth: Option<Frequency>,
+    /// IF Reference Frequency
+    pub if_reference_frequency: Option<Frequency>,
+    /// RF Reference Frequency
+    pub rf_reference_frequency: Option<Frequency>,
+    /// RF Reference Frequency Offset
+    pub rf_reference_frequency_offset: Option<Frequency>,
+    /// IF Band Offset
+    pub if_band_offset: Option<Frequency>,
+    /// Reference Level
+    pub reference_level: Option<Gain>,
+    /// Gain
+    pub gain: Option<GainStages>,
+    /// Over-Range Count
+    pub over_range_count: Option<u32>,
+    /// Sample Rate
+    pub sample_rate: Option<Frequency>,
+    /// Timestamp Adjustment
+    pub timestamp_adjustment: Option<i64>,
+    /// Timestamp Calibration Time
+    pub timestamp_calibration_time: Option<u32>,
+    /// Temperature
+    pub temperature: Option<Temperature>,
+    /// Device Identifier
+    pub device_identifier: Option<DeviceIdentifier>,
+    /// State and Event Indicators
+    pub state_event_indicators: Option<StateEventIndicators>,
+    /// Data Packet Payload Format
+    pub data_payload_format: Option<PayloadFormat>,
+    /// Formatted GPS Geolocation
+    pub formatted_gps: Option<Geolocation>,
+    /// Formatted INS Geolocation
+    pub formatted_ins: Option<Geolocation>,
+    /// ECEF Ephemeris
+    pub ecef_ephemeris: Option<Ephemeris>,
+    /// Relative Ephemeris
+    pub relative_ephemeris: Option<Ephemeris>,
+    /// Ephemeris Reference Identifier
+    pub ephemeris_reference_id: Option<u32>,
+    /// CIF1 fields, except the arrays of records, which borrow the packet and are always `None`
+    pub cif1: Cif1<'static>,
+    /// CIF2 fields
+    pub cif2: Cif2,
+    /// CIF3 fields
+    pub cif3: Cif3,
+}
+
+/// Copy the fields present in `src` into `dst`, keeping the other fields of `dst`.
+macro_rules! merge_fields {
+    ($dst:expr, $src:expr, $($field:ident),* $(,)?) => {
+        $( $dst.$field = $src.$field.or($dst.$field); )*
+    };
+}
+
+impl StreamContext {
+    /// Apply the fields of a context packet.
+    fn apply(&mut self, context: &ContextPacket<'_>, timestamp: Option<Timestamp>) {
+        self.class_id = context.class_id.or(self.class_id);
+        self.timestamp = timestamp;
+        merge_fields!(
+            self,
+            context.cif0,
+            reference_point_id,
+            bandwidth,
+            if_reference_frequency,
+            rf_reference_frequency,
+            rf_reference_frequency_offset,
+            if_band_offset,
+            reference_level,
+            gain,
+            over_range_count,
+            sample_rate,
+            timestamp_adjustment,
+            timestamp_calibration_time,
+            temperature,
+            device_identifier,
+            state_event_indicators,
+            data_payload_format,
+            formatted_gps,
+            formatted_ins,
+            ecef_ephemeris,
+            relative_ephemeris,
+            ephemeris_reference_id,
+        );
+        if let Some(cif1) = &context.cif1 {
+            merge_fields!(
+                self.cif1,
+                cif1,
+                phase_offset,
+                polarization,
+                pointing_vector,
+                spatial_scan_type,
+                spatial_reference_type,
+                beam_widths,
+                range,
+                eb_no_ber,
+                threshold,
+                compression_point,
+                intercept_points,
+                snr_noise_figure,
+                aux_frequency,
+                aux_gain,
+                aux_bandwidth,
+                spectrum,
+                discrete_io32,
+                discrete_io64,
+                health_status,
+                v49_spec_compliance,
+                version_build_code,
+                buffer_size,
+            );
+        }
+        if let Some(cif2) = &context.cif2 {
+            merge_fields!(
+                self.cif2,
+                cif2,
+                bind,
+                cited_sid,
+                sibling_sid,
+                parent_sid,
+                child_sid,
+                cited_message_id,
+                controllee_id,
+                controllee_uuid,
+                controller_id,
+                controller_uuid,
+                information_source,
+                track_id,
+                country_code,
+                operator,
+                platform_class,
+                platform_instance,
+                platform_display,
+                ems_device_class,
+                ems_device_type,
+                ems_device_instance,
+                modulation_class,
+                modulation_type,
+                function_id,
+                mode_id,
+                event_id,
+                function_priority_id,
+                communication_priority_id,
+                rf_footprint,
+                rf_footprint_range,
+            );
+        }
+        if let Some(cif3) = &context.cif3 {
+            merge_fields!(
+                self.cif3,
+                cif3,
+                timestamp_details,
+                timestamp_skew,
+                rise_time,
+                fall_time,
+                offset_time,
+                pulse_width,
+                period,
+                duration,
+                dwell,
+                jitter,
+                age,
+                shelf_life,
+                air_temperature,
+                sea_ground_temperature,
+                humidity,
+                barometric_pressure,
+                sea_swell_state,
+                tropospheric_state,
+                network_id,
+            );
+        }
+    }
+}
+
+/// Information class of a Class ID: its OUI and Information Class Code.
+type InformationClass = Option<(Oui, u16)>;
+
+fn information_class(class_id: Option<ClassId>) -> InformationClass {
+    class_id.map(|class_id| (class_id.oui, class_id.information_class_code))
+}
+
+/// Tracks the context of each stream so that data packets can be interpreted.
+///
+/// Context is tracked per Stream ID and information class, the OUI and Information Class Code of
+/// the Class ID, so context streams of different classes sharing a Stream ID stay apart. Data
+/// packets are paired with the context stream of the same Stream ID unless
+/// [`StreamTracker::associate`] names another one, and of the same information class, falling
+/// back to context without a Class ID.
+#[derive(Clone, Debug, Default)]
+pub struct StreamTracker {
+    contexts: BTreeMap<(u32, InformationClass), StreamContext>,
+    associations: BTreeMap<u32, u32>,
+}
+
+impl StreamTracker {
+    /// Create a tracker without any streams.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pair the data stream `data_stream_id` with the context stream `context_stream_id`.
+    pub fn associate(&mut self, data_stream_id: u32, context_stream_id: u32) {
+        self.associations.insert(data_stream_id, context_stream_id);
+    }
+
+    /// Update the tracked context from a packet.
+    ///
+    /// Returns `true` if the packet is an IF Context packet whose context fields changed, as
+    /// given by its Context Field Change Indicator, or the first context packet of its stream and
+    /// class. Other packets are ignored, including Extension Context packets, whose payload is
+    /// defined by their packet class.
+    pub fn update(&mut self, packet: &VrtPacket<'_>) -> Result<bool, Error> {
+        if packet.header.packet_type != PktType::IfContext {
+            return Ok(false);
+        }
+        let timestamp = packet.timestamp();
+        let context = ContextPacket::try_from(packet.clone())?;
+
+        let mut changed = context.cif0.change_indicator;
+        let key = (context.stream_id, information_class(context.class_id));
+        let state = self.contexts.entry(key).or_insert_with(|| {
+            changed = true;
+            StreamContext::default()
+        });
+        state.apply(&context, timestamp);
+
+        Ok(changed)
+    }
+
+    /// Latest context of the context stream `stream_id` with the information class of `class_id`.
+    pub fn context(&self, stream_id: u32, class_id: Option<ClassId>) -> Option<&StreamContext> {
+        self.contexts.get(&(stream_id, information_class(class_id)))
+    }
+
+    /// Latest context of the context stream paired with the stream and class of `packet`.
+    pub fn context_for(&self, packet: &VrtPacket<'_>) -> Option<&StreamContext> {
+        let stream_id = packet.stream_id?;
+        let context_stream_id = self
+            .associations
+            .get(&stream_id)
+            .copied()
+            .unwrap_or(stream_id);
+        self.context(context_stream_id, packet.class_id)
+            .or_else(|| self.context(context_stream_id, None))
+    }
+
+    /// Stop tracking the context stream `stream_id` with the information class of `class_id`,
+    /// returning its latest context.
+    pub fn remove(&mut self, stream_id: u32, class_id: Option<ClassId>) -> Option<StreamContext> {
+        self.contexts
+            .remove(&(stream_id, information_class(class_id)))
+    }
+}
@@ -0,0 +1,295 @@
+use core::{
+    cmp::Ordering,
+    ops::{Add, Sub},
+    time::Duration,
+};
+
+use super::*;
+
+/// VRT timestamp combining the Integer-seconds and Fractional-seconds Timestamps.
+///
+/// Timestamps are ordered only against timestamps of the same [`Tsi`] and [`Tsf`] types.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Timestamp {
+    /// Type of the Integer-seconds Timestamp
+    pub tsi: Tsi,
+    /// Integer-seconds Timestamp, zero when `tsi` is [`Tsi::None`]
+    pub integer: u32,
+    /// Type of the Fractional-seconds Timestamp
+    pub tsf: Tsf,
+    /// Fractional-seconds Timestamp, zero when `tsf` is [`Tsf::None`]
+    pub fractional: u64,
+}
+
+impl Timestamp {
+    /// Number of picoseconds in a second.
+    pub const PICOSECONDS_PER_SECOND: u64 = 1_000_000_000_000;
+    /// The GPS epoch (1980-01-06 00:00:00 UTC) in seconds since the UTC epoch (1970-01-01).
+    pub const GPS_EPOCH: u32 = 315_964_800;
+
+    /// Create a real-time timestamp from seconds and picoseconds since the epoch of `tsi`.
+    pub fn real_time(tsi: Tsi, seconds: u32, picoseconds: u64) -> Self {
+        Timestamp {
+            tsi,
+            integer: seconds,
+            tsf: Tsf::RealTime,
+            fractional: picoseconds,
+        }
+    }
+
+    /// Create a real-time timestamp from the time since the epoch of `tsi`.
+    ///
+    /// Returns `None` if the seconds do not fit in the Integer-seconds Timestamp.
+    pub fn from_duration(tsi: Tsi, duration: Duration) -> Option<Self> {
+        let seconds = u32::try_from(duration.as_secs()).ok()?;
+        let picoseconds = duration.subsec_nanos() as u64 * 1000;
+        Some(Self::real_time(tsi, seconds, picoseconds))
+    }
+
+    /// Seconds and picoseconds since the epoch of the Integer-seconds Timestamp.
+    ///
+    /// Returns `None` unless the timestamp has an Integer-seconds Timestamp and either no
+    /// Fractional-seconds Timestamp or a valid real-time one.
+    pub fn seconds_picoseconds(&self) -> Option<(u32, u64)> {
+        if self.tsi == Tsi::None {
+            return None;
+        }
+        match self.tsf {
+            Tsf::None => Some((self.integer, 0)),
+            Tsf::RealTime if self.fractional < Self::PICOSECONDS_PER_SECOND => {
+                Some((self.integer, self.fractional))
+            }
+            _ => None,
+        }
+    }
+
+    /// Time since the epoch of the Integer-seconds Timestamp, truncated to nanoseconds.
+    pub fn to_duration(&self) -> Option<Duration> {
+        let (seconds, picoseconds) = self.seconds_picoseconds()?;
+        Some(Duration::new(seconds as u64, (picoseconds / 1000) as u32))
+    }
+
+    /// Add `duration` to a real-time timestamp, returning `None` on overflow.
+    ///
+    /// Timestamps without a Fractional-seconds Timestamp only accept whole seconds.
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        let total = self
+            .total_picoseconds()?
+            .checked_add(picoseconds(duration))?;
+        self.with_total_picoseconds(total)
+    }
+
+    /// Subtract `duration` from a real-time timestamp, returning `None` on underflow.
+    ///
+    /// Timestamps without a Fractional-seconds Timestamp only accept whole seconds.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        let total = self
+            .total_picoseconds()?
+            .checked_sub(picoseconds(duration))?;
+        self.with_total_picoseconds(total)
+    }
+
+    /// Time elapsed from `earlier` to this timestamp, truncated to nanoseconds.
+    ///
+    /// Returns `None` if the timestamps are of different types, are not real-time, or
+    /// `earlier` is later than this timestamp.
+    pub fn duration_since(&self, earlier: &Timestamp) -> Option<Duration> {
+        if (self.tsi, self.tsf) != (earlier.tsi, earlier.tsf) {
+            return None;
+        }
+        let picoseconds = self
+            .total_picoseconds()?
+            .checked_sub(earlier.total_picoseconds()?)?;
+        let seconds = picoseconds / Self::PICOSECONDS_PER_SECOND as u128;
+        let nanoseconds = (picoseconds % Self::PICOSECONDS_PER_SECOND as u128) / 1000;
+        Some(Duration::new(seconds as u64, nanoseconds as u32))
+    }
+
+    /// Convert a GPS timestamp to UTC.
+    ///
+    /// `leap_seconds` lists `(utc, offset)` entries sorted by `utc`: from `utc` (seconds since
+    /// the UTC epoch) onward, GPS time is ahead of UTC by `offset` seconds. Returns `None` if the
+    /// timestamp is not a GPS timestamp or the result overflows.
+    pub fn gps_to_utc(&self, leap_seconds: &[(u32, u32)]) -> Option<Self> {
+        if self.tsi != Tsi::Gps {
+            return None;
+        }
+        let gps = self.integer.checked_add(Self::GPS_EPOCH)?;
+        let offset = leap_seconds
+            .iter()
+            .take_while(|&&(utc, offset)| utc.saturating_add(offset) <= gps)
+            .last()
+            .map_or(0, |&(_, offset)| offset);
+
+        Some(Timestamp {
+            tsi: Tsi::Utc,
+            integer: gps - offset,
+            ..*self
+        })
+    }
+
+    /// Convert a UTC timestamp to GPS, using a `leap_seconds` table as for [`Self::gps_to_utc`].
+    ///
+    /// Returns `None` if the timestamp is not a UTC timestamp or precedes the GPS epoch.
+    pub fn utc_to_gps(&self, leap_seconds: &[(u32, u32)]) -> Option<Self> {
+        if self.tsi != Tsi::Utc {
+            return None;
+        }
+        let offset = leap_seconds
+            .iter()
+            .take_while(|&&(utc, _)| utc <= self.integer)
+            .last()
+            .map_or(0, |&(_, offset)| offset);
+
+        Some(Timestamp {
+            tsi: Tsi::Gps,
+            integer: self
+                .integer
+                .checked_add(offset)?
+                .checked_sub(Self::GPS_EPOCH)?,
+            ..*self
+        })
+    }
+
+    /// Real-time timestamp of sample `index` of a payload whose first sample has this
+    /// sample-count timestamp, for a stream sampled at `sample_rate`.
+    ///
+    /// Samples past the end of the second carry over into the integer seconds. Returns `None`
+    /// unless this is a sample-count timestamp with an Integer-seconds Timestamp, or if the
+    /// sample rate is not positive.
+    pub fn sample_time(&self, sample_rate: Frequency, index: u64) -> Option<Timestamp> {
+        let rate = self.sample_count_rate(sample_rate)?;
+        let count = (self.fractional as u128).checked_add(index as u128)?;
+        // The sample period is 2^RADIX / rate seconds.
+        let picoseconds =
+            count * Self::PICOSECONDS_PER_SECOND as u128 * (1 << Frequency::RADIX) / rate;
+        let seconds = u32::try_from(picoseconds / Self::PICOSECONDS_PER_SECOND as u128).ok()?;
+
+        Some(Timestamp::real_time(
+            self.tsi,
+            self.integer.checked_add(seconds)?,
+            (picoseconds % Self::PICOSECONDS_PER_SECOND as u128) as u64,
+        ))
+    }
+
+    /// Sample-count timestamp of the sample `samples` after this one, for a stream sampled at
+    /// `sample_rate`.
+    ///
+    /// The sample count rolls over into the integer seconds at each one-second boundary.
+    pub fn advance_samples(&self, sample_rate: Frequency, samples: u64) -> Option<Timestamp> {
+        let per_second = self.samples_per_second(sample_rate)?;
+        let count = self.fractional.checked_add(samples)?;
+        let seconds = u32::try_from(count / per_second).ok()?;
+
+        Some(Timestamp {
+            integer: self.integer.checked_add(seconds)?,
+            fractional: count % per_second,
+            ..*self
+        })
+    }
+
+    /// Index of the first of the next `samples` samples that falls in the following second,
+    /// or `None` if they all fall within the second of this sample-count timestamp.
+    pub fn sample_count_rollover(&self, sample_rate: Frequency, samples: u64) -> Option<u64> {
+        let per_second = self.samples_per_second(sample_rate)?;
+        let remaining = per_second.saturating_sub(self.fractional);
+        (remaining < samples).then_some(remaining)
+    }
+
+    /// Number of sample counts in one second, the smallest count not within the second.
+    fn samples_per_second(&self, sample_rate: Frequency) -> Option<u64> {
+        let rate = self.sample_count_rate(sample_rate)?;
+        u64::try_from(rate.div_ceil(1 << Frequency::RADIX)).ok()
+    }
+
+    /// Raw fixed-point sample rate, if this is an absolute sample-count timestamp.
+    fn sample_count_rate(&self, sample_rate: Frequency) -> Option<u128> {
+        if self.tsi == Tsi::None || self.tsf != Tsf::SampleCount || sample_rate.raw() <= 0 {
+            return None;
+        }
+        Some(sample_rate.raw() as u128)
+    }
+
+    /// Picoseconds since the epoch of the Integer-seconds Timestamp.
+    fn total_picoseconds(&self) -> Option<u128> {
+        let (seconds, picoseconds) = self.seconds_picoseconds()?;
+        Some(seconds as u128 * Self::PICOSECONDS_PER_SECOND as u128 + picoseconds as u128)
+    }
+
+    /// Timestamp of the same types at `total` picoseconds since the epoch.
+    fn with_total_picoseconds(&self, total: u128) -> Option<Self> {
+        let seconds = total / Self::PICOSECONDS_PER_SECOND as u128;
+        let picoseconds = (total % Self::PICOSECONDS_PER_SECOND as u128) as u64;
+        if self.tsf == Tsf::None && picoseconds != 0 {
+            return None;
+        }
+
+        Some(Timestamp {
+            integer: u32::try_from(seconds).ok()?,
+            fractional: picoseconds,
+            ..*self
+        })
+    }
+}
+
+/// Length of `duration` in picoseconds.
+fn picoseconds(duration: Duration) -> u128 {
+    duration.as_nanos() * 1000
+}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if (self.tsi, self.tsf) != (other.tsi, other.tsf) {
+            return None;
+        }
+        Some((self.integer, self.fractional).cmp(&(other.integer, other.fractional)))
+    }
+}
+
+impl Add<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    /// # Panics
+    ///
+    /// Panics if the timestamp is not real-time or the result overflows. See
+    /// [`Timestamp::checked_add`].
+    fn add(self, rhs: Duration) -> Timestamp {
+        self.checked_add(rhs)
+            .expect("overflow when adding duration to timestamp")
+    }
+}
+
+impl Sub<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    /// # Panics
+    ///
+    /// Panics if the timestamp is not real-time or the result underflows. See
+    /// [`Timestamp::checked_sub`].
+    fn sub(self, rhs: Duration) -> Timestamp {
+        self.checked_sub(rhs)
+            .expect("overflow when subtracting duration from timestamp")
+    }
+}
+
+impl VrtPacket<'_> {
+    /// Timestamp of the packet, or `None` if it has no timestamp fields.
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        if self.tsi.is_none() && self.tsf.is_none() {
+            return None;
+        }
+
+        Some(Timestamp {
+            tsi: self.header.tsi,
+            integer: self.tsi.unwrap_or(0),
+            tsf: self.header.tsf,
+            fractional: self.tsf.unwrap_or(0),
+        })
+    }
+
+    /// Real-time timestamp of sample `index` of the data payload, for a stream sampled at
+    /// `sample_rate`. See [`Timestamp::sample_time`].
+    pub fn sample_time(&self, sample_rate: Frequency, index: u64) -> Option<Timestamp> {
+        self.timestamp()?.sample_time(sample_rate, index)
+    }
+}
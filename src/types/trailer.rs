@@ -1,7 +1,13 @@
 use nom::{number::streaming::be_u8, Err, IResult, Needed};
 
+use super::ensure_capacity;
+use crate::Error;
+
 /// VRT Packet Trailer
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+///
+/// Each indicator is only meaningful when its enable bit is set; the accessor methods such as
+/// [`Trailer::sample_loss`] return `None` otherwise.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct Trailer {
     /// Indicates whether or not the timestamp in teh IF Data packet is calibrated to an external reference.
     pub calibrated_time_enable: bool,
@@ -58,10 +64,88 @@ pub struct Trailer {
 }
 
 impl Trailer {
+    /// Size of the encoded trailer in bytes.
+    pub const SIZE: usize = size_of::<u32>();
+
+    /// Start building a trailer with [`TrailerBuilder`].
+    pub fn builder() -> TrailerBuilder {
+        TrailerBuilder::default()
+    }
+
+    /// Is the timestamp calibrated to an external reference?
+    pub fn calibrated_time(&self) -> Option<bool> {
+        self.calibrated_time_enable
+            .then_some(self.calibrated_time_indicator)
+    }
+
+    /// Is the data in the packet valid?
+    pub fn valid_data(&self) -> Option<bool> {
+        self.valid_data_enable.then_some(self.valid_data_indicator)
+    }
+
+    /// Are the phase-locked loops affecting the data locked and stable?
+    pub fn reference_lock(&self) -> Option<bool> {
+        self.reference_lock_enable
+            .then_some(self.reference_lock_indicator)
+    }
+
+    /// Is AGC (rather than MGC) active?
+    pub fn agc(&self) -> Option<bool> {
+        self.agcmgc_enable.then_some(self.agcmgc_indicator)
+    }
+
+    /// Does the data contain a detected signal?
+    pub fn detected_signal(&self) -> Option<bool> {
+        self.detected_signal_enable
+            .then_some(self.detected_signal_indicator)
+    }
+
+    /// Is the spectrum of the signal inverted?
+    pub fn spectral_inversion(&self) -> Option<bool> {
+        self.spectral_inversion_enable
+            .then_some(self.spectral_inversion_indicator)
+    }
+
+    /// Is at least one sample invalid because the signal exceeded the range of the data item?
+    pub fn overrange(&self) -> Option<bool> {
+        self.overrange_enable.then_some(self.overrange_indicator)
+    }
+
+    /// Does the packet contain at least one sample discontinuity?
+    pub fn sample_loss(&self) -> Option<bool> {
+        self.sample_loss_enable
+            .then_some(self.sample_loss_indicator)
+    }
+
+    /// User-defined indicator `n`, from 1 to 4.
+    pub fn user_defined(&self, n: u8) -> Option<bool> {
+        match n {
+            1 => self
+                .user_defined_enable_1
+                .then_some(self.user_defined_indicator_1),
+            2 => self
+                .user_defined_enable_2
+                .then_some(self.user_defined_indicator_2),
+            3 => self
+                .user_defined_enable_3
+                .then_some(self.user_defined_indicator_3),
+            4 => self
+                .user_defined_enable_4
+                .then_some(self.user_defined_indicator_4),
+            _ => None,
+        }
+    }
+
+    /// Count of the context packets associated with the data packet.
+    pub fn associated_context_packet_count(&self) -> Option<u8> {
+        self.associated_context_packet_count_enable
+            .then_some(self.associated_context_packet_count)
+    }
+
     /// Parse the VRT packet trailer
     pub fn parse(i: &[u8]) -> IResult<&[u8], Trailer> {
         if i.len() < 4 {
-            return Err(Err::Incomplete(Needed::new(4)));
+            return Err(Err::Incomplete(Needed::new(Self::SIZE - i.len())));
         }
 
         let (i, first_byte) = be_u8(i)?;
@@ -133,4 +217,154 @@ impl Trailer {
         };
         Ok((i, hdr))
     }
+
+    /// Encode the VRT packet trailer into `buf`, returning the number of bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        ensure_capacity(buf, Self::SIZE)?;
+
+        // first byte
+        buf[0] = ((self.calibrated_time_enable as u8) << 7)
+            | ((self.valid_data_enable as u8) << 6)
+            | ((self.reference_lock_enable as u8) << 5)
+            | ((self.agcmgc_enable as u8) << 4)
+            | ((self.detected_signal_enable as u8) << 3)
+            | ((self.spectral_inversion_enable as u8) << 2)
+            | ((self.overrange_enable as u8) << 1)
+            | (self.sample_loss_enable as u8);
+
+        // second byte
+        buf[1] = ((self.user_defined_enable_1 as u8) << 7)
+            | ((self.user_defined_enable_2 as u8) << 6)
+            | ((self.user_defined_enable_3 as u8) << 5)
+            | ((self.user_defined_enable_4 as u8) << 4)
+            | ((self.calibrated_time_indicator as u8) << 3)
+            | ((self.valid_data_indicator as u8) << 2)
+            | ((self.reference_lock_indicator as u8) << 1)
+            | (self.agcmgc_indicator as u8);
+
+        // third byte
+        buf[2] = ((self.detected_signal_indicator as u8) << 7)
+            | ((self.spectral_inversion_indicator as u8) << 6)
+            | ((self.overrange_indicator as u8) << 5)
+            | ((self.sample_loss_indicator as u8) << 4)
+            | ((self.user_defined_indicator_1 as u8) << 3)
+            | ((self.user_defined_indicator_2 as u8) << 2)
+            | ((self.user_defined_indicator_3 as u8) << 1)
+            | (self.user_defined_indicator_4 as u8);
+
+        // fourth byte
+        buf[3] = ((self.associated_context_packet_count_enable as u8) << 7)
+            | (self.associated_context_packet_count & 0x7f);
+
+        Ok(Self::SIZE)
+    }
+}
+
+/// Builder for [`Trailer`]s that sets the enable bit of each indicator it is given.
+///
+/// ```
+/// use vrt::Trailer;
+///
+/// let trailer = Trailer::builder().valid_data(true).sample_loss(false).build();
+/// assert_eq!(trailer.valid_data(), Some(true));
+/// assert_eq!(trailer.sample_loss(), Some(false));
+/// assert_eq!(trailer.calibrated_time(), None);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TrailerBuilder(Trailer);
+
+impl TrailerBuilder {
+    /// Set the calibrated time indicator.
+    pub fn calibrated_time(mut self, value: bool) -> Self {
+        self.0.calibrated_time_enable = true;
+        self.0.calibrated_time_indicator = value;
+        self
+    }
+
+    /// Set the valid data indicator.
+    pub fn valid_data(mut self, value: bool) -> Self {
+        self.0.valid_data_enable = true;
+        self.0.valid_data_indicator = value;
+        self
+    }
+
+    /// Set the reference lock indicator.
+    pub fn reference_lock(mut self, value: bool) -> Self {
+        self.0.reference_lock_enable = true;
+        self.0.reference_lock_indicator = value;
+        self
+    }
+
+    /// Set the AGC/MGC indicator, `true` when AGC is active.
+    pub fn agc(mut self, value: bool) -> Self {
+        self.0.agcmgc_enable = true;
+        self.0.agcmgc_indicator = value;
+        self
+    }
+
+    /// Set the detected signal indicator.
+    pub fn detected_signal(mut self, value: bool) -> Self {
+        self.0.detected_signal_enable = true;
+        self.0.detected_signal_indicator = value;
+        self
+    }
+
+    /// Set the spectral inversion indicator.
+    pub fn spectral_inversion(mut self, value: bool) -> Self {
+        self.0.spectral_inversion_enable = true;
+        self.0.spectral_inversion_indicator = value;
+        self
+    }
+
+    /// Set the over-range indicator.
+    pub fn overrange(mut self, value: bool) -> Self {
+        self.0.overrange_enable = true;
+        self.0.overrange_indicator = value;
+        self
+    }
+
+    /// Set the sample loss indicator.
+    pub fn sample_loss(mut self, value: bool) -> Self {
+        self.0.sample_loss_enable = true;
+        self.0.sample_loss_indicator = value;
+        self
+    }
+
+    /// Set user-defined indicator `n`, from 1 to 4. Other values of `n` are ignored.
+    pub fn user_defined(mut self, n: u8, value: bool) -> Self {
+        let (enable, indicator) = match n {
+            1 => (
+                &mut self.0.user_defined_enable_1,
+                &mut self.0.user_defined_indicator_1,
+            ),
+            2 => (
+                &mut self.0.user_defined_enable_2,
+                &mut self.0.user_defined_indicator_2,
+            ),
+            3 => (
+                &mut self.0.user_defined_enable_3,
+                &mut self.0.user_defined_indicator_3,
+            ),
+            4 => (
+                &mut self.0.user_defined_enable_4,
+                &mut self.0.user_defined_indicator_4,
+            ),
+            _ => return self,
+        };
+        *enable = true;
+        *indicator = value;
+        self
+    }
+
+    /// Set the 7-bit associated context packet count.
+    pub fn associated_context_packet_count(mut self, count: u8) -> Self {
+        self.0.associated_context_packet_count_enable = true;
+        self.0.associated_context_packet_count = count & 0x7f;
+        self
+    }
+
+    /// Build the trailer.
+    pub fn build(self) -> Trailer {
+        self.0
+    }
 }
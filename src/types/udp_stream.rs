@@ -0,0 +1,213 @@
+use alloc::vec::Vec;
+use core::{
+    future::poll_fn,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use std::net::SocketAddr;
+
+use futures_core::Stream;
+use futures_sink::Sink;
+use socket2::SockRef;
+use tokio::{
+    io::ReadBuf,
+    net::{ToSocketAddrs, UdpSocket},
+};
+
+use super::*;
+use crate::Error;
+
+/// Asynchronous stream of VRT packets received on a tokio [`UdpSocket`].
+///
+/// Each datagram may carry several VRT packets, which are yielded in order. A packet that fails
+/// to parse yields an error and the rest of its datagram is dropped. The stream is also a
+/// [`Sink`] of packets to send, each paired with its destination address.
+///
+/// ```no_run
+/// use futures::StreamExt;
+/// use vrt::VrtUdpStream;
+///
+/// # async fn run() -> Result<(), vrt::Error> {
+/// let mut stream = VrtUdpStream::bind("0.0.0.0:4991").await?;
+/// while let Some(packet) = stream.next().await {
+///     let packet = packet?;
+///     println!("{:?} from {:?}", packet.stream_id, stream.source_addr());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct VrtUdpStream {
+    socket: UdpSocket,
+    datagram: DatagramBuffer,
+    source: Option<SocketAddr>,
+    send_buf: Vec<u8>,
+    destination: Option<SocketAddr>,
+}
+
+impl VrtUdpStream {
+    /// Default size of the datagram buffer, large enough for any UDP datagram.
+    pub const DEFAULT_BUFFER_SIZE: usize = DatagramBuffer::DEFAULT_SIZE;
+
+    /// Receive and send packets on `socket`.
+    pub fn new(socket: UdpSocket) -> Self {
+        VrtUdpStream {
+            socket,
+            datagram: DatagramBuffer::new(Self::DEFAULT_BUFFER_SIZE),
+            source: None,
+            send_buf: Vec::new(),
+            destination: None,
+        }
+    }
+
+    /// Bind a new UDP socket to `addr`.
+    pub async fn bind(addr: impl ToSocketAddrs) -> Result<Self, Error> {
+        Ok(Self::new(UdpSocket::bind(addr).await?))
+    }
+
+    /// Receive datagrams into a buffer of `size` bytes; longer datagrams are truncated.
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        self.datagram = DatagramBuffer::new(size);
+        self
+    }
+
+    /// Set the socket receive buffer size (`SO_RCVBUF`) in bytes.
+    ///
+    /// A larger buffer absorbs bursts of packets that arrive while the stream is not polled.
+    pub fn set_recv_buffer_size(&self, size: usize) -> Result<(), Error> {
+        Ok(SockRef::from(&self.socket).set_recv_buffer_size(size)?)
+    }
+
+    /// Socket receive buffer size (`SO_RCVBUF`) in bytes.
+    pub fn recv_buffer_size(&self) -> Result<usize, Error> {
+        Ok(SockRef::from(&self.socket).recv_buffer_size()?)
+    }
+
+    /// Address that sent the most recently received packet.
+    pub fn source_addr(&self) -> Option<SocketAddr> {
+        self.source
+    }
+
+    /// Borrow the underlying socket.
+    pub fn get_ref(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Return the underlying socket, dropping any packets not yet received.
+    pub fn into_inner(self) -> UdpSocket {
+        self.socket
+    }
+
+    /// Receive the next packet along with the address that sent it.
+    pub async fn recv_from(&mut self) -> Result<(OwnedVrtPacket, SocketAddr), Error> {
+        poll_fn(|cx| self.poll_recv_from(cx)).await
+    }
+
+    /// Poll for the next packet along with the address that sent it.
+    pub fn poll_recv_from(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(OwnedVrtPacket, SocketAddr), Error>> {
+        let source = match self.source {
+            Some(source) if !self.datagram.is_empty() => source,
+            _ => loop {
+                let mut buf = ReadBuf::new(self.datagram.recv_buf());
+                let source = ready!(self.socket.poll_recv_from(cx, &mut buf))?;
+                let len = buf.filled().len();
+                self.datagram.filled(len);
+                self.source = Some(source);
+                if len > 0 {
+                    break source;
+                }
+            },
+        };
+        let packet = self.datagram.next_packet()?;
+        Poll::Ready(Ok((packet.to_owned_packet(), source)))
+    }
+
+    /// Encode `packet` and send it to `target`.
+    pub async fn send_to(&self, packet: &VrtPacket<'_>, target: SocketAddr) -> Result<(), Error> {
+        let buf = packet.encode_to_vec()?;
+        let len = self.socket.send_to(&buf, target).await?;
+        check_sent(len, buf.len())
+    }
+
+    /// Encode `packet` into the send buffer, to be sent to `target` on the next flush.
+    fn start_send_packet(
+        &mut self,
+        packet: &VrtPacket<'_>,
+        target: SocketAddr,
+    ) -> Result<(), Error> {
+        self.send_buf.resize(packet.encoded_len(), 0);
+        packet.encode(&mut self.send_buf)?;
+        self.destination = Some(target);
+        Ok(())
+    }
+
+    /// Send the buffered packet, if any.
+    fn poll_send_buffered(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        if let Some(target) = self.destination {
+            let len = ready!(self.socket.poll_send_to(cx, &self.send_buf, target))?;
+            self.destination = None;
+            check_sent(len, self.send_buf.len())?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Stream for VrtUdpStream {
+    type Item = Result<OwnedVrtPacket, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut()
+            .poll_recv_from(cx)
+            .map(|result| Some(result.map(|(packet, _)| packet)))
+    }
+}
+
+impl<'a> Sink<(VrtPacket<'a>, SocketAddr)> for VrtUdpStream {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_send_buffered(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        (packet, target): (VrtPacket<'a>, SocketAddr),
+    ) -> Result<(), Error> {
+        self.get_mut().start_send_packet(&packet, target)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_send_buffered(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_send_buffered(cx)
+    }
+}
+
+impl Sink<(OwnedVrtPacket, SocketAddr)> for VrtUdpStream {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_send_buffered(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        (packet, target): (OwnedVrtPacket, SocketAddr),
+    ) -> Result<(), Error> {
+        self.get_mut()
+            .start_send_packet(&packet.as_packet(), target)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_send_buffered(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_send_buffered(cx)
+    }
+}
@@ -0,0 +1,317 @@
+use alloc::{vec, vec::Vec};
+
+use nom::number::complete::be_u32;
+
+use super::*;
+use crate::Error;
+
+/// Severity of a [`Finding`].
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// Allowed by VITA 49 but likely a mistake, or not interoperable.
+    Warning,
+    /// Violates a VITA 49 rule.
+    Error,
+}
+
+/// VITA 49 rule checked by the packet validators.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rule {
+    /// The packet cannot be parsed.
+    Unparseable(Error),
+    /// The packet size does not match the length of the buffer holding the packet.
+    PacketSizeMismatch {
+        /// Packet size, in bytes.
+        packet_size: usize,
+        /// Buffer length, in bytes.
+        buffer_len: usize,
+    },
+    /// The header indicator bits or packet size disagree with the packet contents.
+    HeaderMismatch(Error),
+    /// The trailer indicator bit is set on a context or command packet.
+    TrailerOnNonDataPacket,
+    /// Reserved bits of a field are set.
+    ReservedBits {
+        /// Name of the field.
+        field: &'static str,
+        /// The reserved bits that are set.
+        bits: u32,
+    },
+    /// A real-time Fractional-seconds Timestamp is present without an Integer-seconds Timestamp.
+    RealTimeTsfWithoutTsi,
+    /// A field holds a value outside its valid range.
+    OutOfRange {
+        /// Name of the field.
+        field: &'static str,
+    },
+    /// The Class ID pad-bit count is non-zero on a packet without a data payload, or exceeds the
+    /// payload.
+    PadBitCount(u8),
+    /// A context field is present whose meaning depends on an absent Integer-seconds Timestamp.
+    FieldRequiresTsi {
+        /// Name of the field.
+        field: &'static str,
+    },
+    /// VITA 49.2 fields are present but the Not a V49.0 Packet Indicator is clear.
+    V49_2FieldsWithoutIndicator,
+    /// The UUID format is selected for an identifier that is not present.
+    UuidWithoutId {
+        /// Name of the identifier.
+        field: &'static str,
+    },
+    /// Timed execution is requested in a command packet without a timestamp.
+    TimingControlWithoutTimestamp,
+    /// An acknowledge packet sets more than one of the validation, execution and query-state
+    /// acknowledgement types.
+    MultipleAcknowledgeTypes,
+}
+
+/// Rule violation found by a packet validator.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Finding {
+    /// How serious the violation is
+    pub severity: Severity,
+    /// The rule that is violated
+    pub rule: Rule,
+}
+
+impl Finding {
+    fn error(rule: Rule) -> Self {
+        Finding {
+            severity: Severity::Error,
+            rule,
+        }
+    }
+
+    fn warning(rule: Rule) -> Self {
+        Finding {
+            severity: Severity::Warning,
+            rule,
+        }
+    }
+}
+
+/// Check the rules shared by all packet types.
+fn check_prologue(
+    findings: &mut Vec<Finding>,
+    header: &Header,
+    class_id: Option<ClassId>,
+    tsf: Option<u64>,
+    payload_len: Option<usize>,
+) {
+    if header.tsi == Tsi::None && header.tsf == Tsf::RealTime {
+        findings.push(Finding::warning(Rule::RealTimeTsfWithoutTsi));
+    }
+    if header.tsf == Tsf::RealTime
+        && tsf.is_some_and(|picoseconds| picoseconds >= Timestamp::PICOSECONDS_PER_SECOND)
+    {
+        findings.push(Finding::error(Rule::OutOfRange {
+            field: "fractional-seconds timestamp",
+        }));
+    }
+
+    if let Some(class_id) = class_id {
+        let pad_bits = class_id.pad_bit_count;
+        let is_data = !header.packet_type.is_context() && !header.packet_type.is_command();
+        let exceeds_payload = payload_len.is_some_and(|len| pad_bits as usize >= len * 8);
+        if pad_bits != 0 && (!is_data || exceeds_payload) {
+            findings.push(Finding::error(Rule::PadBitCount(pad_bits)));
+        }
+    }
+}
+
+impl VrtPacket<'_> {
+    /// Check the packet against the VITA 49 rules, returning every violation found.
+    ///
+    /// The fields of IF Context and Command packets are checked as well. The payload of
+    /// Extension Context and Extension Command packets is defined by their packet class and is
+    /// not checked.
+    pub fn validate(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        if let Err(e) = self.check_header() {
+            findings.push(Finding::error(Rule::HeaderMismatch(e)));
+        }
+        check_prologue(
+            &mut findings,
+            &self.header,
+            self.class_id,
+            self.tsf,
+            Some(self.payload.len()),
+        );
+
+        let fields = match self.header.packet_type {
+            PktType::IfContext => {
+                ContextPacket::try_from(self.clone()).map(|packet| packet.validate_fields())
+            }
+            PktType::Command => {
+                CommandPacket::try_from(self.clone()).map(|packet| packet.validate_fields())
+            }
+            _ => Ok(Vec::new()),
+        };
+        match fields {
+            Ok(fields) => findings.extend(fields),
+            Err(e) => findings.push(Finding::error(Rule::Unparseable(e))),
+        }
+        findings
+    }
+
+    /// Check the encoded packet at the start of `bytes` against the VITA 49 rules, returning every
+    /// violation found.
+    ///
+    /// Unlike [`VrtPacket::validate`], this also checks reserved bits that parsing discards and
+    /// that the packet fills `bytes`.
+    pub fn validate_bytes(bytes: &[u8]) -> Vec<Finding> {
+        let (packet, _) = match VrtPacket::from_bytes(bytes) {
+            Ok(packet) => packet,
+            Err(e) => return vec![Finding::error(Rule::Unparseable(e))],
+        };
+        let mut findings = Vec::new();
+
+        let packet_size = packet.header.packet_size as usize * size_of::<u32>();
+        if packet_size != bytes.len() {
+            findings.push(Finding::warning(Rule::PacketSizeMismatch {
+                packet_size,
+                buffer_len: bytes.len(),
+            }));
+        }
+
+        let unused = (bytes[0] & 0b111) & !packet.header.indicators.bits();
+        if unused & 0b100 != 0 {
+            findings.push(Finding::error(Rule::TrailerOnNonDataPacket));
+        }
+        if unused & 0b011 != 0 {
+            findings.push(Finding::error(Rule::ReservedBits {
+                field: "header",
+                bits: ((unused & 0b011) as u32) << 24,
+            }));
+        }
+
+        let packet_type = packet.header.packet_type;
+        if packet.class_id.is_some() {
+            let offset = Header::SIZE + packet_type.has_stream_id() as usize * size_of::<u32>();
+            if let Ok((_, word)) = be_u32::<_, nom::error::Error<_>>(&bytes[offset..]) {
+                // Bits 26 to 24 of the first Class ID word are reserved.
+                let reserved = word & 0x0700_0000;
+                if reserved != 0 {
+                    findings.push(Finding::error(Rule::ReservedBits {
+                        field: "class id",
+                        bits: reserved,
+                    }));
+                }
+            }
+        }
+        if packet_type == PktType::IfContext {
+            if let Ok((_, word0)) = be_u32::<_, nom::error::Error<_>>(packet.payload) {
+                // Bits 6 to 4 and 0 of CIF0 are reserved.
+                let reserved = word0 & 0x71;
+                if reserved != 0 {
+                    findings.push(Finding::error(Rule::ReservedBits {
+                        field: "cif0",
+                        bits: reserved,
+                    }));
+                }
+            }
+        } else if packet_type == PktType::Command {
+            if let (Ok(command), Ok((_, word))) = (
+                CommandPacket::try_from(packet.clone()),
+                be_u32::<_, nom::error::Error<_>>(packet.payload),
+            ) {
+                let reserved = word ^ u32::from(command.cam);
+                if reserved != 0 {
+                    findings.push(Finding::error(Rule::ReservedBits {
+                        field: "cam",
+                        bits: reserved,
+                    }));
+                }
+            }
+        }
+
+        findings.extend(packet.validate());
+        findings
+    }
+}
+
+impl ContextPacket<'_> {
+    /// Check the packet against the VITA 49 rules, returning every violation found.
+    pub fn validate(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        check_prologue(&mut findings, &self.header, self.class_id, self.tsf, None);
+        findings.extend(self.validate_fields());
+        findings
+    }
+
+    /// Check the context fields.
+    fn validate_fields(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let cif0 = &self.cif0;
+        if cif0.bandwidth.is_some_and(|bandwidth| bandwidth.raw() < 0) {
+            findings.push(Finding::error(Rule::OutOfRange { field: "bandwidth" }));
+        }
+        if cif0.sample_rate.is_some_and(|rate| rate.raw() <= 0) {
+            findings.push(Finding::error(Rule::OutOfRange {
+                field: "sample rate",
+            }));
+        }
+        if cif0.timestamp_calibration_time.is_some() && self.header.tsi == Tsi::None {
+            findings.push(Finding::warning(Rule::FieldRequiresTsi {
+                field: "timestamp calibration time",
+            }));
+        }
+
+        let v49_2_fields = self.cif1.is_some() || self.cif2.is_some() || self.cif3.is_some();
+        if v49_2_fields
+            && matches!(
+                self.header.indicators,
+                Indicators::Context {
+                    not_v49_0: false,
+                    ..
+                }
+            )
+        {
+            findings.push(Finding::warning(Rule::V49_2FieldsWithoutIndicator));
+        }
+        findings
+    }
+}
+
+impl CommandPacket<'_> {
+    /// Check the packet against the VITA 49 rules, returning every violation found.
+    pub fn validate(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        check_prologue(&mut findings, &self.header, self.class_id, self.tsf, None);
+        findings.extend(self.validate_fields());
+        findings
+    }
+
+    /// Check the command fields.
+    fn validate_fields(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let cam = &self.cam;
+        if cam.controllee_uuid && !cam.controllee_enable {
+            findings.push(Finding::warning(Rule::UuidWithoutId {
+                field: "controllee id",
+            }));
+        }
+        if cam.controller_uuid && !cam.controller_enable {
+            findings.push(Finding::warning(Rule::UuidWithoutId {
+                field: "controller id",
+            }));
+        }
+        if cam.timing_control != 0 && self.tsi.is_none() && self.tsf.is_none() {
+            findings.push(Finding::error(Rule::TimingControlWithoutTimestamp));
+        }
+
+        let acknowledge = matches!(
+            self.header.indicators,
+            Indicators::Command {
+                acknowledge: true,
+                ..
+            }
+        );
+        let ack_types = cam.validation as u8 + cam.execution as u8 + cam.query_state as u8;
+        if acknowledge && ack_types > 1 {
+            findings.push(Finding::error(Rule::MultipleAcknowledgeTypes));
+        }
+        findings
+    }
+}
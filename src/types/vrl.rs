@@ -0,0 +1,220 @@
+use nom::{
+    bytes::streaming::take, error::ErrorKind, number::streaming::be_u32, Err, IResult, Needed,
+    Parser,
+};
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use super::*;
+use crate::Error;
+
+/// VITA 49.1 VRT Link (VRL) frame carrying one or more VRT packets.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VrlFrame<'a> {
+    /// Modulo-4096 count of frames on the link.
+    pub frame_count: u16,
+    /// Encoded VRT packets carried by the frame.
+    pub packets: &'a [u8],
+    /// Does the frame trailer carry a CRC rather than the "VEND" pattern?
+    pub crc: bool,
+}
+
+impl<'a> VrlFrame<'a> {
+    /// Frame alignment word, "VRLP" in ASCII.
+    pub const ALIGNMENT_WORD: u32 = 0x5652_4c50;
+    /// Frame trailer of frames without a CRC, "VEND" in ASCII.
+    pub const NO_CRC_WORD: u32 = 0x5645_4e44;
+    /// Largest frame size, in 32-bit words.
+    pub const MAX_SIZE: usize = 0xf_ffff;
+    /// Number of bytes in the frame header and trailer.
+    const OVERHEAD: usize = 3 * size_of::<u32>();
+
+    /// Create a frame carrying the encoded `packets`, protected by a CRC.
+    pub fn new(frame_count: u16, packets: &'a [u8]) -> Self {
+        VrlFrame {
+            frame_count: frame_count & 0xfff,
+            packets,
+            crc: true,
+        }
+    }
+
+    /// Parse the VRL frame, validating its alignment word, size and CRC
+    pub fn parse(i: &'a [u8]) -> IResult<&'a [u8], VrlFrame<'a>> {
+        let frame = i;
+        let (i, alignment_word) = be_u32(i)?;
+        if alignment_word != Self::ALIGNMENT_WORD {
+            return Err(Err::Error(nom::error::Error::new(frame, ErrorKind::Verify)));
+        }
+        let (i, word) = be_u32(i)?;
+        let frame_count = (word >> 20) as u16;
+        let frame_size = (word & 0xf_ffff) as usize * size_of::<u32>();
+        if frame_size < Self::OVERHEAD {
+            return Err(Err::Error(nom::error::Error::new(frame, ErrorKind::Verify)));
+        }
+        if frame.len() < frame_size {
+            return Err(Err::Incomplete(Needed::new(frame_size - frame.len())));
+        }
+
+        let (i, packets) = take(frame_size - Self::OVERHEAD)(i)?;
+        let (i, trailer) = be_u32(i)?;
+        let crc = trailer != Self::NO_CRC_WORD;
+        if crc && trailer != crc32(&frame[..frame_size - size_of::<u32>()]) {
+            return Err(Err::Error(nom::error::Error::new(frame, ErrorKind::Verify)));
+        }
+
+        Ok((
+            i,
+            VrlFrame {
+                frame_count,
+                packets,
+                crc,
+            },
+        ))
+    }
+
+    /// Parse the VRL frame at the start of `bytes`, returning it along with its length in bytes.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<(VrlFrame<'a>, usize), Error> {
+        let e = match VrlFrame::parse(bytes) {
+            Ok((rest, frame)) => return Ok((frame, bytes.len() - rest.len())),
+            Err(e @ Err::Incomplete(_)) => return Err(Error::from_nom(bytes, e, "frame")),
+            Err(e) => e,
+        };
+
+        let (_, (alignment_word, word)) = (be_u32, be_u32)
+            .parse(bytes)
+            .map_err(|e| Error::from_nom(bytes, e, "frame"))?;
+        if alignment_word != Self::ALIGNMENT_WORD {
+            return Err(Error::InvalidField {
+                field: "alignment word",
+                offset: 0,
+            });
+        }
+        let size = (word & 0xf_ffff) as usize * size_of::<u32>();
+        if size < Self::OVERHEAD {
+            return Err(Error::SizeMismatch {
+                size,
+                min_size: Self::OVERHEAD,
+            });
+        }
+        if let (Some(frame), Some(trailer)) = (
+            bytes.get(..size - size_of::<u32>()),
+            bytes.get(size - size_of::<u32>()..size),
+        ) {
+            let received = u32::from_be_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+            return Err(Error::CrcMismatch {
+                received,
+                computed: crc32(frame),
+            });
+        }
+        Err(Error::from_nom(bytes, e, "frame"))
+    }
+
+    /// Iterate over the VRT packets carried by the frame.
+    pub fn iter(&self) -> VrlPackets<'a> {
+        VrlPackets(self.packets)
+    }
+
+    /// Number of frames lost between a frame counted `previous` and this one, or `None` if this
+    /// frame repeats the count of the previous one, such as a duplicated or replayed frame.
+    pub fn frames_missed_since(&self, previous: u16) -> Option<u16> {
+        let step = self.frame_count.wrapping_sub(previous) & 0xfff;
+        step.checked_sub(1)
+    }
+
+    /// Number of bytes needed to encode the VRL frame
+    pub fn encoded_len(&self) -> usize {
+        Self::OVERHEAD + self.packets.len()
+    }
+
+    /// Encode the VRL frame into `buf`, returning the number of bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        if self.packets.len() % size_of::<u32>() != 0 {
+            return Err(Error::PayloadAlignment(self.packets.len()));
+        }
+        let len = self.encoded_len();
+        let words = len / size_of::<u32>();
+        if words > Self::MAX_SIZE {
+            return Err(Error::FrameTooLarge(words));
+        }
+        ensure_capacity(buf, len)?;
+
+        let word = ((self.frame_count as u32 & 0xfff) << 20) | words as u32;
+        buf[0..4].copy_from_slice(&Self::ALIGNMENT_WORD.to_be_bytes());
+        buf[4..8].copy_from_slice(&word.to_be_bytes());
+        buf[8..len - 4].copy_from_slice(self.packets);
+        let trailer = if self.crc {
+            crc32(&buf[..len - 4])
+        } else {
+            Self::NO_CRC_WORD
+        };
+        buf[len - 4..len].copy_from_slice(&trailer.to_be_bytes());
+
+        Ok(len)
+    }
+
+    /// Encode the VRL frame into a newly allocated buffer.
+    #[cfg(feature = "alloc")]
+    pub fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        let mut buf = vec![0; self.encoded_len()];
+        self.encode(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// Iterator over the VRT packets carried by a [`VrlFrame`].
+///
+/// Iteration stops after the first packet that fails to parse.
+#[derive(Clone, Debug)]
+pub struct VrlPackets<'a>(&'a [u8]);
+
+impl<'a> Iterator for VrlPackets<'a> {
+    type Item = Result<VrtPacket<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+        match VrtPacket::from_bytes(self.0) {
+            Ok((packet, len)) => {
+                self.0 = &self.0[len..];
+                Some(Ok(packet))
+            }
+            Err(e) => {
+                self.0 = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// CRC-32 lookup table for the reflected polynomial 0x04C11DB7.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut crc = n as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[n] = crc;
+        n += 1;
+    }
+    table
+};
+
+/// CRC-32 of a VRL frame.
+///
+/// Uses the IEEE 802.3 (Ethernet) CRC-32: polynomial 0x04C11DB7, reflected input and output,
+/// initial value and final XOR of 0xFFFFFFFF, computed over the frame bytes preceding the trailer.
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
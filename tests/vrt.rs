@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use rstest::rstest;
 use vrt::*;
 
@@ -39,6 +41,49 @@ static VRT_FLEXRADIO_BROADCAST_MSG: &[u8] = &[
     0x64, 0x6c, 0x65, 0x73, 0x3d, 0x00, 0x00, 0x00,
 ];
 
+static VRT_IF_CONTEXT_MSG: &[u8] = &[
+    0x40, 0x60, 0x00, 0x19, 0x00, 0x00, 0x00, 0x01, 0x5f, 0x5e, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0xa9, 0xa5, 0x03, 0x00, 0x00, 0x00, 0x13, 0x12, 0xd0, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x5f, 0x5e, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0xfb, 0x00, 0x05, 0x00, 0xfe, 0x40,
+    0x00, 0x00, 0x17, 0xd7, 0x84, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a, 0x20, 0xa0, 0x08, 0x00, 0x5a,
+    0x00, 0x00, 0x1c, 0x2d, 0x00, 0x00, 0x00, 0x02, 0x24, 0x47, 0x50, 0x47, 0x47, 0x41, 0x00, 0x00,
+    0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x80, 0x01, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x20,
+    0x00, 0x00, 0x00, 0x30,
+];
+
+static VRT_IF_CONTEXT_V49_2_MSG: &[u8] = &[
+    0x40, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00, 0x02, 0x20, 0x00, 0x00, 0x0e, 0x80, 0x00, 0x04, 0x02,
+    0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x82, 0x00, 0x00, 0x00, 0xf4, 0x24, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x04, 0x00,
+    0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3e, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf4,
+    0x24, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xfe, 0x00,
+    0x00, 0x00, 0x01, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x01,
+    0x00, 0x00, 0x12, 0x34, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67,
+    0x89, 0xab, 0xcd, 0xef, 0x00, 0x00, 0x06, 0x40, 0x00, 0x00, 0x00, 0x2a,
+];
+
+/// Control packet setting the RF reference frequency to 100 MHz
+static VRT_CONTROL_MSG: &[u8] = &[
+    0x60, 0x00, 0x00, 0x08, // header
+    0x00, 0x00, 0x00, 0x01, // stream id
+    0x81, 0x10, 0x00, 0x00, // CAM
+    0x00, 0x00, 0x00, 0x2a, // message id
+    0x00, 0x00, 0x00, 0x07, // controllee id
+    0x08, 0x00, 0x00, 0x00, // CIF0
+    0x00, 0x00, 0x5f, 0x5e, 0x10, 0x00, 0x00, 0x00, // RF reference frequency
+];
+
+/// Validation acknowledge packet warning that the RF reference frequency is out of range
+static VRT_VALIDATION_ACK_MSG: &[u8] = &[
+    0x64, 0x00, 0x00, 0x07, // header
+    0x00, 0x00, 0x00, 0x01, // stream id
+    0x80, 0x12, 0x00, 0x00, // CAM
+    0x00, 0x00, 0x00, 0x2a, // message id
+    0x00, 0x00, 0x00, 0x07, // controllee id
+    0x08, 0x00, 0x00, 0x00, // WIF0
+    0x10, 0x00, 0x00, 0x00, // RF reference frequency warning
+];
+
 static VRT_STATIC_TRAILER_TEST_PATTERN: &[u8] = &[0xAA, 0xAA, 0xAA, 0xAA];
 
 #[test]
@@ -47,7 +92,11 @@ fn test_parse_vrt_header() {
     let expected = Header {
         packet_type: PktType::ExtDataWithStream,
         c: true,
-        t: false,
+        indicators: Indicators::Data {
+            trailer: false,
+            not_v49_0: false,
+            spectrum: false,
+        },
         tsi: Tsi::Utc,
         tsf: Tsf::SampleCount,
         packet_count: 15,
@@ -57,6 +106,55 @@ fn test_parse_vrt_header() {
     assert_eq!(res, expected);
 }
 
+#[test]
+fn test_parse_vrt_header_large_packet_size() {
+    let (_, res) = Header::parse(&[0x10, 0x00, 0xff, 0xff]).expect("failed to parse header");
+    assert_eq!(res.packet_size, 0xffff);
+    let (_, res) = Header::parse(&[0x10, 0x00, 0x01, 0x02]).expect("failed to parse header");
+    assert_eq!(res.packet_size, 0x0102);
+}
+
+#[rstest]
+#[case(0x17, Indicators::Data { trailer: true, not_v49_0: true, spectrum: true }, 0x17)]
+#[case(0x13, Indicators::Data { trailer: false, not_v49_0: true, spectrum: true }, 0x13)]
+#[case(0x43, Indicators::Context { not_v49_0: true, tsm: true }, 0x43)]
+#[case(0x46, Indicators::Context { not_v49_0: true, tsm: false }, 0x42)]
+#[case(0x65, Indicators::Command { acknowledge: true, cancel: true }, 0x65)]
+#[case(0x72, Indicators::Command { acknowledge: false, cancel: false }, 0x70)]
+fn parse_header_indicators(
+    #[case] first_byte: u8,
+    #[case] expected: Indicators,
+    #[case] encoded: u8,
+) {
+    let (_, res) = Header::parse(&[first_byte, 0x00, 0x00, 0x01]).expect("failed to parse header");
+    assert_eq!(res.indicators, expected);
+
+    // Reserved indicator bits are not preserved.
+    let mut buf = [0; 4];
+    res.encode(&mut buf).expect("failed to encode header");
+    assert_eq!(buf[0], encoded);
+}
+
+#[test]
+fn test_build_vrt_packet_indicators() {
+    let packet = VrtPacketBuilder::new(PktType::IfContext)
+        .stream_id(1)
+        .indicators(Indicators::Context {
+            not_v49_0: true,
+            tsm: true,
+        })
+        .build()
+        .expect("failed to build packet");
+    let mut buf = [0; 8];
+    assert_eq!(packet.encode(&mut buf), Ok(8));
+    assert_eq!(buf[..4], [0x43, 0x00, 0x00, 0x02]);
+
+    let res = VrtPacketBuilder::new(PktType::IfData)
+        .indicators(Indicators::new(PktType::Command))
+        .build();
+    assert_eq!(res, Err(Error::HeaderMismatch("indicators")));
+}
+
 #[test]
 fn test_parse_vrt_header_tooshort() {
     let bytes = &VRT_FLEXRADIO_BROADCAST_MSG[0..2];
@@ -113,12 +211,16 @@ fn test_parse_vrt_packet() {
     let bytes = VRT_FLEXRADIO_BROADCAST_MSG;
     let (_, res) = VrtPacket::parse(bytes).expect("failed to parse packet");
     assert_eq!(res.stream_id, Some(2048));
-    const FLEX_RADIO_OUI: u32 = 0x001C2D;
-    let (oui, info_class_code, packet_class_code) = res
-        .class_id
-        .map(|v| (v.oui, v.information_class_code, v.packet_class_code))
-        .expect("Missing ClassId");
-    assert_eq!(oui, FLEX_RADIO_OUI);
+    const FLEX_RADIO_OUI: Option<Oui> = Oui::new(0x001C2D);
+    let class_id = res.class_id.expect("Missing ClassId");
+    let (pad_bit_count, oui, info_class_code, packet_class_code) = (
+        class_id.pad_bit_count,
+        class_id.oui,
+        class_id.information_class_code,
+        class_id.packet_class_code,
+    );
+    assert_eq!(pad_bit_count, 0);
+    assert_eq!(Some(oui), FLEX_RADIO_OUI);
     assert_eq!(info_class_code, 0x534c);
     assert_eq!(packet_class_code, 0xffff);
     assert_eq!(res.payload, b"discovery_protocol_version=3.0.0.1 model=FLEX-6700 serial=1121-1104-6700-2912 version=3.2.39.3374 nickname=PSOC2/HF/AMP callsign=W4BR ip=192.168.0.11 port=4992 status=Available inuse_ip= inuse_host= max_licensed_version=v3 radio_license_id=00-1C-2D-02-0F-EA requires_additional_license=0 fpc_mac= wan_connected=1 licensed_clients=2 available_clients=2 max_panadapters=8 available_panadapters=8 max_slices=8 available_slices=8 gui_client_ips= gui_client_hosts= gui_client_programs= gui_client_stations= gui_client_handles=\0\0\0");
@@ -131,7 +233,9 @@ fn test_parse_vrt_packet() {
 #[case(3, Ok(PktType::ExtDataWithStream))]
 #[case(4, Ok(PktType::IfContext))]
 #[case(5, Ok(PktType::ExtContext))]
-#[case(6, Err(Error::PktType(6)))]
+#[case(6, Ok(PktType::Command))]
+#[case(7, Ok(PktType::ExtCommand))]
+#[case(8, Err(Error::PktType(8)))]
 fn parse_pkt_type(#[case] input: u8, #[case] expected: Result<PktType, Error>) {
     let pkt_type = PktType::try_from(input);
 
@@ -161,3 +265,1921 @@ fn parse_tsf(#[case] input: &[u8], #[case] expected: Result<Tsf, Error>) {
 
     assert_eq!(tsi, expected);
 }
+
+#[test]
+fn test_encode_vrt_trailer() {
+    let (_, trailer) =
+        Trailer::parse(VRT_STATIC_TRAILER_TEST_PATTERN).expect("failed to parse trailer");
+    let mut buf = [0u8; 4];
+    let len = trailer.encode(&mut buf).expect("failed to encode trailer");
+    assert_eq!(len, 4);
+    assert_eq!(buf, VRT_STATIC_TRAILER_TEST_PATTERN);
+}
+
+#[test]
+fn test_encode_vrt_packet() {
+    let (_, packet) =
+        VrtPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG).expect("failed to parse packet");
+    let mut buf = [0u8; 1024];
+    let len = packet.encode(&mut buf).expect("failed to encode packet");
+    assert_eq!(&buf[..len], VRT_FLEXRADIO_BROADCAST_MSG);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_encode_vrt_packet_to_vec() {
+    let (_, packet) =
+        VrtPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG).expect("failed to parse packet");
+    let buf = packet.encode_to_vec().expect("failed to encode packet");
+    assert_eq!(buf, VRT_FLEXRADIO_BROADCAST_MSG);
+}
+
+#[test]
+fn test_encode_vrt_packet_tooshort() {
+    let (_, packet) =
+        VrtPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG).expect("failed to parse packet");
+    let mut buf = [0u8; 16];
+    assert_eq!(
+        packet.encode(&mut buf),
+        Err(Error::BufferTooSmall {
+            needed: VRT_FLEXRADIO_BROADCAST_MSG.len(),
+            available: 16
+        })
+    );
+}
+
+#[test]
+fn test_encode_vrt_packet_header_mismatch() {
+    let (_, mut packet) =
+        VrtPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG).expect("failed to parse packet");
+    packet.class_id = None;
+    let mut buf = [0u8; 1024];
+    assert_eq!(
+        packet.encode(&mut buf),
+        Err(Error::HeaderMismatch("class_id"))
+    );
+}
+
+#[test]
+fn test_build_vrt_packet() {
+    let (_, expected) =
+        VrtPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG).expect("failed to parse packet");
+    let packet = VrtPacketBuilder::new(PktType::ExtDataWithStream)
+        .packet_count(15)
+        .stream_id(2048)
+        .class_id(expected.class_id.unwrap())
+        .tsi(Tsi::Utc, expected.tsi.unwrap())
+        .tsf(Tsf::SampleCount, expected.tsf.unwrap())
+        .payload(expected.payload)
+        .build()
+        .expect("failed to build packet");
+    assert_eq!(packet, expected);
+}
+
+#[rstest]
+#[case(
+    VrtPacketBuilder::new(PktType::IfDataWithStream),
+    Err(Error::HeaderMismatch("stream_id"))
+)]
+#[case(VrtPacketBuilder::new(PktType::IfData).stream_id(1), Err(Error::HeaderMismatch("stream_id")))]
+#[case(VrtPacketBuilder::new(PktType::IfData).payload(&[0; 3]), Err(Error::PayloadAlignment(3)))]
+#[case(VrtPacketBuilder::new(PktType::IfData).trailer(Trailer::parse(&[0; 4]).unwrap().1), Ok(2))]
+fn build_vrt_packet_size(
+    #[case] builder: VrtPacketBuilder<'_>,
+    #[case] expected: Result<u16, Error>,
+) {
+    let packet_size = builder.build().map(|packet| packet.header.packet_size);
+
+    assert_eq!(packet_size, expected);
+}
+
+#[test]
+fn test_parse_vrt_context_packet() {
+    let (_, res) =
+        ContextPacket::parse(VRT_IF_CONTEXT_MSG).expect("failed to parse context packet");
+    assert_eq!(res.stream_id, 1);
+    assert_eq!(res.tsi, Some(0x5f5e1000));
+    assert_eq!(res.tsf, Some(0));
+
+    let cif0 = res.cif0;
+    assert!(cif0.change_indicator);
+    assert_eq!(cif0.reference_point_id, None);
+    assert_eq!(cif0.bandwidth.map(Frequency::to_f64), Some(20e6));
+    assert_eq!(cif0.if_reference_frequency, None);
+    assert_eq!(
+        cif0.rf_reference_frequency.map(Frequency::to_f64),
+        Some(100e6)
+    );
+    assert_eq!(cif0.reference_level.map(Gain::to_f64), Some(-10.0));
+    assert_eq!(
+        cif0.gain,
+        Some(GainStages {
+            stage1: Gain::from_f64(-3.5),
+            stage2: Gain::from_f64(10.0)
+        })
+    );
+    assert_eq!(cif0.sample_rate.map(Frequency::to_f64), Some(25e6));
+    assert_eq!(cif0.temperature.map(Temperature::to_f64), Some(40.5));
+
+    let indicators = cif0
+        .state_event_indicators
+        .expect("missing state/event indicators");
+    assert!(indicators.calibrated_time_enable);
+    assert!(indicators.calibrated_time_indicator);
+    assert!(indicators.reference_lock_enable);
+    assert!(!indicators.reference_lock_indicator);
+    assert!(!indicators.valid_data_enable);
+    assert_eq!(indicators.user_defined, 0x5a);
+
+    let gps_ascii = cif0.gps_ascii.expect("missing GPS ASCII");
+    assert_eq!(gps_ascii.manufacturer_oui, Oui::new(0x1c2d).unwrap());
+    assert_eq!(gps_ascii.data, b"$GPGGA\0\0");
+
+    let lists = cif0
+        .context_association_lists
+        .expect("missing context association lists");
+    assert_eq!(lists.source.iter().collect::<Vec<_>>(), [0x10]);
+    assert!(lists.system.is_empty());
+    assert!(lists.vector_component.is_empty());
+    assert_eq!(
+        lists.asynchronous_channel.iter().collect::<Vec<_>>(),
+        [0x20]
+    );
+    assert_eq!(
+        lists
+            .asynchronous_channel_tags
+            .map(|tags| tags.iter().collect::<Vec<_>>()),
+        Some(vec![0x30])
+    );
+}
+
+#[test]
+fn test_parse_vrt_context_packet_v49_2() {
+    let (_, res) =
+        ContextPacket::parse(VRT_IF_CONTEXT_V49_2_MSG).expect("failed to parse context packet");
+    assert_eq!(res.stream_id, 2);
+    assert_eq!(
+        res.cif0.bandwidth,
+        Some(Frequency::from_raw(1_000_000 << 20))
+    );
+
+    let cif1 = res.cif1.expect("missing CIF1");
+    assert_eq!(cif1.phase_offset.map(Phase::to_f64), Some(1.0));
+    let spectrum = cif1.spectrum.expect("missing spectrum");
+    assert_eq!(spectrum.num_transform_points, 1024);
+    assert_eq!(spectrum.span.to_f64(), 1e6);
+    assert_eq!(spectrum.f1_index, -512);
+    assert_eq!(spectrum.f2_index, 511);
+    assert_eq!(cif1.buffer_size, Some(0x0000_1000_0000_0001));
+    assert_eq!(cif1.polarization, None);
+
+    let cif2 = res.cif2.expect("missing CIF2");
+    assert_eq!(cif2.controllee_id, Some(0x1234));
+    assert_eq!(
+        cif2.controllee_uuid,
+        Some(0x0123456789abcdef0123456789abcdef)
+    );
+    assert_eq!(cif2.controller_id, None);
+
+    let cif3 = res.cif3.expect("missing CIF3");
+    assert_eq!(cif3.air_temperature, Some(Temperature::from_f64(25.0)));
+    assert_eq!(cif3.network_id, Some(42));
+}
+
+#[test]
+fn test_parse_vrt_context_packet_not_context() {
+    let res = ContextPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG);
+    assert!(matches!(res, Err(nom::Err::Error(_))));
+}
+
+#[test]
+fn test_parse_vrt_context_packet_truncated_fields() {
+    let mut bytes = VRT_IF_CONTEXT_MSG[..40].to_vec();
+    bytes[3] = 10;
+    let res = ContextPacket::parse(&bytes);
+    assert!(matches!(res, Err(nom::Err::Error(_))));
+}
+
+#[test]
+fn test_parse_vrt_control_packet() {
+    let (rem, res) = CommandPacket::parse(VRT_CONTROL_MSG).expect("failed to parse control packet");
+    assert!(rem.is_empty());
+    assert_eq!(res.header.packet_type, PktType::Command);
+    assert_eq!(res.stream_id, 1);
+    assert_eq!(
+        res.cam,
+        Cam {
+            controllee_enable: true,
+            action_mode: ActionMode::Execute,
+            validation: true,
+            ..Cam::default()
+        }
+    );
+    assert_eq!(u32::from(res.cam), 0x8110_0000);
+    assert_eq!(res.message_id, 42);
+    assert_eq!(res.controllee_id, Some(ControlId::Id(7)));
+    assert_eq!(res.controller_id, None);
+
+    let CommandKind::Control(fields) = res.kind else {
+        panic!("expected a control packet, got {:?}", res.kind);
+    };
+    assert_eq!(
+        fields.cif0.rf_reference_frequency,
+        Some(Frequency::from_f64(100e6))
+    );
+    assert_eq!(fields.cif1, None);
+}
+
+#[test]
+fn test_parse_vrt_validation_ack_packet() {
+    let (_, res) =
+        CommandPacket::parse(VRT_VALIDATION_ACK_MSG).expect("failed to parse ack packet");
+    assert_eq!(
+        res.header.indicators,
+        Indicators::Command {
+            acknowledge: true,
+            cancel: false
+        }
+    );
+    assert_eq!(res.message_id, 42);
+
+    let CommandKind::ValidationAck(ack) = res.kind else {
+        panic!("expected a validation ack packet, got {:?}", res.kind);
+    };
+    assert_eq!(ack.errors, None);
+    let warnings = ack.warnings.expect("missing warnings");
+    assert_eq!(warnings.indicators.word0, 0x0800_0000);
+    let flags = AckFlags {
+        parameter_out_of_range: true,
+        ..AckFlags::default()
+    };
+    assert_eq!(warnings.iter().collect::<Vec<_>>(), [((0, 27), flags)]);
+    assert_eq!(warnings.get(0, 27), Some(flags));
+    assert_eq!(warnings.get(0, 29), None);
+}
+
+#[test]
+fn test_parse_vrt_cancel_and_query_ack_packets() {
+    let cancel: &[u8] = &[
+        0x61, 0x00, 0x00, 0x05, // header
+        0x00, 0x00, 0x00, 0x01, // stream id
+        0x00, 0x00, 0x00, 0x00, // CAM
+        0x00, 0x00, 0x00, 0x2b, // message id
+        0x08, 0x00, 0x00, 0x00, // CIF0
+    ];
+    let (_, res) = CommandPacket::parse(cancel).expect("failed to parse cancel packet");
+    let CommandKind::Cancel(cif) = res.kind else {
+        panic!("expected a cancel packet, got {:?}", res.kind);
+    };
+    assert_eq!(cif.fields().collect::<Vec<_>>(), [(0, 27)]);
+
+    let query_ack: &[u8] = &[
+        0x64, 0x00, 0x00, 0x09, // header
+        0x00, 0x00, 0x00, 0x01, // stream id
+        0x30, 0x04, 0x00, 0x00, // CAM
+        0x00, 0x00, 0x00, 0x2c, // message id
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, // controller UUID
+        0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff, //
+        0x00, 0x00, 0x00, 0x00, // CIF0
+    ];
+    let (_, res) = CommandPacket::parse(query_ack).expect("failed to parse query ack packet");
+    assert_eq!(
+        res.controller_id,
+        Some(ControlId::Uuid(0x0011_2233_4455_6677_8899_aabb_ccdd_eeff))
+    );
+    assert!(matches!(res.kind, CommandKind::QueryAck(_)));
+}
+
+#[test]
+fn test_parse_vrt_command_packet_not_command() {
+    let res = CommandPacket::parse(VRT_IF_CONTEXT_MSG);
+    assert!(matches!(res, Err(nom::Err::Error(_))));
+}
+
+#[rstest]
+#[case(Frequency::from_f64(14_074_000.0), 14_074_000.0, 14_074_000 << 20)]
+#[case(Frequency::from_f64(-0.5), -0.5, -(1 << 19))]
+#[case(Frequency::from_f64(1.0 / (1 << 21) as f64), 1.0 / (1 << 20) as f64, 1)]
+fn frequency_fixed_point(
+    #[case] value: Frequency,
+    #[case] expected_f64: f64,
+    #[case] expected_raw: i64,
+) {
+    assert_eq!(value.to_f64(), expected_f64);
+    assert_eq!(value.raw(), expected_raw);
+    assert_eq!(Frequency::from_be_bytes(value.to_be_bytes()), value);
+}
+
+#[rstest]
+#[case(Gain::from_f64(-3.5), -448)]
+#[case(Gain::from_f64(0.0), 0)]
+#[case(Gain::from_f64(1000.0), i16::MAX)]
+#[case(Gain::from_f64(-1000.0), i16::MIN)]
+fn gain_fixed_point(#[case] value: Gain, #[case] expected_raw: i16) {
+    assert_eq!(value.raw(), expected_raw);
+}
+
+#[test]
+fn test_fixed_point_parse() {
+    let (_, temperature) = Temperature::parse(&[0x0a, 0x20]).expect("failed to parse temperature");
+    assert_eq!(temperature.to_f64(), 40.5);
+    assert_eq!(temperature.to_be_bytes(), [0x0a, 0x20]);
+
+    let (_, latitude) = Angle::parse(&[0x0a, 0x80, 0x00, 0x00]).expect("failed to parse angle");
+    assert_eq!(latitude.to_f64(), 42.0);
+}
+
+#[test]
+fn test_parse_environmental_fixed_point() {
+    let (_, cif1) = Cif1::parse_fields(
+        0x0110_0000,
+        &[0x00, 0x00, 0x06, 0x40, 0x05, 0x00, 0xfc, 0x80],
+    )
+    .expect("failed to parse CIF1 fields");
+    assert_eq!(cif1.range.map(RangeDistance::to_f64), Some(25.0));
+    let eb_no_ber = cif1.eb_no_ber.expect("missing Eb/No BER");
+    assert_eq!(eb_no_ber.eb_no.to_f64(), 10.0);
+    assert_eq!(eb_no_ber.bit_error_rate.to_f64(), -7.0);
+
+    let (_, cif3) = Cif3::parse_fields(
+        0x0000_0030,
+        &[0x00, 0x00, 0x32, 0x00, 0x00, 0x00, 0xfd, 0x50],
+    )
+    .expect("failed to parse CIF3 fields");
+    assert_eq!(cif3.humidity.map(Humidity::to_f64), Some(100.0));
+    assert_eq!(
+        cif3.barometric_pressure.map(Pressure::to_f64),
+        Some(1013.25)
+    );
+}
+
+fn payload_format(
+    packing_method: PackingMethod,
+    real_complex: RealComplexType,
+    data_item_format: DataItemFormat,
+    item_packing_field_size: u8,
+    data_item_size: u8,
+) -> PayloadFormat {
+    PayloadFormat {
+        packing_method,
+        real_complex,
+        data_item_format,
+        sample_component_repeat: false,
+        event_tag_size: 0,
+        channel_tag_size: 0,
+        data_item_fraction_size: 0,
+        item_packing_field_size,
+        data_item_size,
+        repeat_count: 1,
+        vector_size: 1,
+    }
+}
+
+#[test]
+fn test_parse_payload_format() {
+    let bytes = [0xa0, 0x03, 0x13, 0xcb, 0x00, 0x00, 0x03, 0xff];
+    let (_, format) = PayloadFormat::parse(&bytes).expect("failed to parse payload format");
+    let expected = PayloadFormat {
+        event_tag_size: 0,
+        channel_tag_size: 3,
+        data_item_fraction_size: 1,
+        vector_size: 1024,
+        ..payload_format(
+            PackingMethod::LinkEfficient,
+            RealComplexType::ComplexCartesian,
+            DataItemFormat::SignedFixed,
+            16,
+            12,
+        )
+    };
+    assert_eq!(format, expected);
+    assert_eq!(u64::from(format), u64::from_be_bytes(bytes));
+}
+
+#[rstest]
+#[case(0x6000_0000_0000_0000, Error::RealComplexType(3))]
+#[case(0x0800_0000_0000_0000, Error::DataItemFormat(8))]
+#[case(0x0000_004f_0000_0000, Error::ItemPackingFieldSize(2))]
+fn parse_payload_format_invalid(#[case] input: u64, #[case] expected: Error) {
+    assert_eq!(PayloadFormat::try_from(input), Err(expected));
+}
+
+#[test]
+fn test_unpack_complex_i16() {
+    let format = payload_format(
+        PackingMethod::ProcessingEfficient,
+        RealComplexType::ComplexCartesian,
+        DataItemFormat::SignedFixed,
+        16,
+        16,
+    );
+    let payload = [0x00, 0x01, 0xff, 0xfe, 0x7f, 0xff, 0x80, 0x00];
+    let samples: Vec<Complex<i16>> = format.samples(&payload).collect();
+    assert_eq!(
+        samples,
+        [
+            Complex { re: 1, im: -2 },
+            Complex {
+                re: i16::MAX,
+                im: i16::MIN
+            }
+        ]
+    );
+
+    let samples: Vec<Complex<f32>> = format.samples(&payload[4..]).collect();
+    assert_eq!(
+        samples,
+        [Complex {
+            re: 32767.0 / 32768.0,
+            im: -1.0
+        }]
+    );
+}
+
+#[rstest]
+#[case(PackingMethod::LinkEfficient, &[0x12, 0x38, 0x00, 0x7f, 0xf0, 0x01], &[0x123, -0x800, 0x7ff, 0x001])]
+#[case(PackingMethod::ProcessingEfficient, &[0x12, 0x38, 0x00, 0x00, 0x7f, 0xf0, 0x01, 0x00], &[0x123, -0x800, 0x7ff, 0x001])]
+fn unpack_12_bit(
+    #[case] packing_method: PackingMethod,
+    #[case] payload: &[u8],
+    #[case] expected: &[i16],
+) {
+    let format = payload_format(
+        packing_method,
+        RealComplexType::Real,
+        DataItemFormat::SignedFixed,
+        12,
+        12,
+    );
+    let samples: Vec<i16> = format.samples(payload).collect();
+    assert_eq!(samples, expected);
+}
+
+#[test]
+fn test_unpack_tags() {
+    let format = PayloadFormat {
+        event_tag_size: 2,
+        channel_tag_size: 4,
+        ..payload_format(
+            PackingMethod::ProcessingEfficient,
+            RealComplexType::Real,
+            DataItemFormat::UnsignedFixed,
+            16,
+            8,
+        )
+    };
+    let items: Vec<DataItem> = format.items(&[0xab, 0x25, 0x00, 0x00]).collect();
+    assert_eq!(
+        items[0],
+        DataItem {
+            value: 0xab,
+            event_tag: 0b10,
+            channel_tag: 0b0101
+        }
+    );
+}
+
+#[rstest]
+#[case(DataItemFormat::Ieee754Single, 32, &[0x3f, 0xc0, 0x00, 0x00], 1.5)]
+#[case(DataItemFormat::Ieee754Double, 64, &[0xc0, 0x04, 0, 0, 0, 0, 0, 0], -2.5)]
+#[case(DataItemFormat::Ieee754Half, 16, &[0x3c, 0x00, 0x00, 0x00], 1.0)]
+#[case(DataItemFormat::UnsignedFixed, 8, &[0x80, 0x00, 0x00, 0x00], 0.5)]
+#[case(DataItemFormat::SignedVrt(2), 8, &[0x43, 0x00, 0x00, 0x00], 0.5)]
+#[case(DataItemFormat::SignedVrt(2), 8, &[0x42, 0x00, 0x00, 0x00], 0.25)]
+fn unpack_f64(
+    #[case] data_item_format: DataItemFormat,
+    #[case] size: u8,
+    #[case] payload: &[u8],
+    #[case] expected: f64,
+) {
+    let format = payload_format(
+        PackingMethod::ProcessingEfficient,
+        RealComplexType::Real,
+        data_item_format,
+        size,
+        size,
+    );
+    assert_eq!(format.samples::<f64>(payload).next(), Some(expected));
+}
+
+#[rstest]
+#[case(
+    DataItemFormat::UnsignedFixedNonNormalized,
+    0x7fff_ffff_ffff_ffff,
+    i64::MAX
+)]
+#[case(
+    DataItemFormat::UnsignedFixedNonNormalized,
+    0x8000_0000_0000_0000,
+    i64::MAX
+)]
+#[case(DataItemFormat::UnsignedFixed, u64::MAX, i64::MAX)]
+#[case(DataItemFormat::SignedFixedNonNormalized, u64::MAX, -1)]
+fn item_to_i64_64_bit(
+    #[case] data_item_format: DataItemFormat,
+    #[case] raw: u64,
+    #[case] expected: i64,
+) {
+    let format = payload_format(
+        PackingMethod::ProcessingEfficient,
+        RealComplexType::Real,
+        data_item_format,
+        64,
+        64,
+    );
+    assert_eq!(format.item_to_i64(raw), expected);
+}
+
+#[rstest]
+#[case(PackingMethod::LinkEfficient, &[0x12, 0x38, 0x00, 0x7f, 0xf0, 0x01, 0x00, 0x00])]
+#[case(PackingMethod::ProcessingEfficient, &[0x12, 0x38, 0x00, 0x00, 0x7f, 0xf0, 0x01, 0x00])]
+fn pack_12_bit(#[case] packing_method: PackingMethod, #[case] expected: &[u8]) {
+    let format = payload_format(
+        packing_method,
+        RealComplexType::Real,
+        DataItemFormat::SignedFixed,
+        12,
+        12,
+    );
+    let samples: [i16; 4] = [0x123, -0x800, 0x7ff, 0x001];
+    let mut buf = [0xffu8; 16];
+    let len = format
+        .pack(&samples, &mut buf)
+        .expect("failed to pack samples");
+    assert_eq!(&buf[..len], expected);
+    assert_eq!(
+        format
+            .samples::<i16>(&buf[..len])
+            .take(4)
+            .collect::<Vec<_>>(),
+        samples
+    );
+}
+
+#[test]
+fn test_pack_complex_i16() {
+    let format = payload_format(
+        PackingMethod::ProcessingEfficient,
+        RealComplexType::ComplexCartesian,
+        DataItemFormat::SignedFixed,
+        16,
+        16,
+    );
+    let samples = [Complex { re: 1i16, im: -2 }, Complex { re: 300, im: -300 }];
+    let mut buf = [0u8; 8];
+    assert_eq!(format.pack(&samples, &mut buf), Ok(8));
+    assert_eq!(buf, [0x00, 0x01, 0xff, 0xfe, 0x01, 0x2c, 0xfe, 0xd4]);
+
+    // Normalized floating-point samples saturate at the edges of the range.
+    let samples = [Complex {
+        re: 0.5f32,
+        im: -2.0,
+    }];
+    assert_eq!(format.pack(&samples, &mut buf), Ok(4));
+    assert_eq!(buf[..4], [0x40, 0x00, 0x80, 0x00]);
+}
+
+#[test]
+fn test_samples_component_repeat() {
+    let format = PayloadFormat {
+        sample_component_repeat: true,
+        vector_size: 2,
+        ..payload_format(
+            PackingMethod::ProcessingEfficient,
+            RealComplexType::ComplexCartesian,
+            DataItemFormat::SignedFixed,
+            16,
+            16,
+        )
+    };
+    // Each vector of two samples holds both real components, then both imaginary components.
+    let payload = [
+        0x00, 0x01, 0x00, 0x02, 0xff, 0xff, 0xff, 0xfe, 0x00, 0x03, 0x00, 0x04, 0xff, 0xfd, 0xff,
+        0xfc,
+    ];
+    let samples = [
+        Complex { re: 1i16, im: -1 },
+        Complex { re: 2, im: -2 },
+        Complex { re: 3, im: -3 },
+        Complex { re: 4, im: -4 },
+    ];
+    assert!(format.samples::<Complex<i16>>(&payload).eq(samples));
+
+    let mut buf = [0u8; 16];
+    assert_eq!(format.pack(&samples, &mut buf), Ok(16));
+    assert_eq!(buf, payload);
+
+    // The data items themselves are read in payload order.
+    let items: Vec<u64> = format.items(&payload).map(|item| item.value).collect();
+    assert_eq!(items[..4], [0x0001, 0x0002, 0xffff, 0xfffe]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_pack_to_vec() {
+    let format = payload_format(
+        PackingMethod::LinkEfficient,
+        RealComplexType::Real,
+        DataItemFormat::UnsignedFixed,
+        14,
+        14,
+    );
+    let payload = format
+        .pack_to_vec(&[1i32, 2, 3])
+        .expect("failed to pack samples");
+    assert_eq!(payload, [0x00, 0x04, 0x00, 0x20, 0x00, 0xc0, 0x00, 0x00]);
+}
+
+#[test]
+fn test_pack_tags() {
+    let format = PayloadFormat {
+        event_tag_size: 2,
+        channel_tag_size: 4,
+        ..payload_format(
+            PackingMethod::ProcessingEfficient,
+            RealComplexType::Real,
+            DataItemFormat::UnsignedFixed,
+            16,
+            8,
+        )
+    };
+    let items = [DataItem {
+        value: 0xab,
+        event_tag: 0b10,
+        channel_tag: 0b0101,
+    }];
+    let mut buf = [0u8; 4];
+    assert_eq!(format.pack_items(&items, &mut buf), Ok(4));
+    assert_eq!(buf, [0xab, 0x25, 0x00, 0x00]);
+    assert_eq!(
+        format.pack_items(&items, &mut buf[..2]),
+        Err(Error::BufferTooSmall {
+            needed: 4,
+            available: 2
+        })
+    );
+}
+
+#[rstest]
+#[case(DataItemFormat::Ieee754Single, 32, 1.5)]
+#[case(DataItemFormat::Ieee754Double, 64, -2.5)]
+#[case(DataItemFormat::Ieee754Half, 16, -0.125)]
+#[case(DataItemFormat::SignedVrt(2), 8, 0.25)]
+#[case(DataItemFormat::SignedVrt(3), 14, -0.0078125)]
+#[case(DataItemFormat::UnsignedVrt(4), 16, 0.75)]
+fn pack_f64_round_trip(
+    #[case] data_item_format: DataItemFormat,
+    #[case] size: u8,
+    #[case] value: f64,
+) {
+    let format = payload_format(
+        PackingMethod::LinkEfficient,
+        RealComplexType::Real,
+        data_item_format,
+        size,
+        size,
+    );
+    let mut buf = [0u8; 8];
+    let len = format
+        .pack(&[value], &mut buf)
+        .expect("failed to pack samples");
+    assert_eq!(len, size.div_ceil(32) as usize * 4);
+    assert_eq!(format.samples::<f64>(&buf[..len]).next(), Some(value));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_stream_decoder_split_reads() {
+    let mut stream = VRT_FLEXRADIO_BROADCAST_MSG.to_vec();
+    stream.extend_from_slice(VRT_IF_CONTEXT_MSG);
+    stream.extend_from_slice(VRT_CONTROL_MSG);
+
+    let mut decoder = VrtStreamDecoder::new();
+    let mut packet_types = Vec::new();
+    for chunk in stream.chunks(7) {
+        decoder.push(chunk);
+        while let Some(packet) = decoder.decode().expect("failed to decode packet") {
+            packet_types.push(packet.header.packet_type);
+        }
+    }
+    assert_eq!(
+        packet_types,
+        [
+            PktType::ExtDataWithStream,
+            PktType::IfContext,
+            PktType::Command
+        ]
+    );
+    assert!(decoder.is_empty());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_stream_decoder_invalid_header() {
+    let mut decoder = VrtStreamDecoder::new();
+    decoder.push(&[0x80, 0x00, 0x00, 0x01]);
+    assert_eq!(decoder.decode(), Err(Error::UnsupportedPacketType(8)));
+    assert!(decoder.is_empty());
+
+    decoder.push(&[0x10, 0x00, 0x00, 0x00]);
+    assert_eq!(
+        decoder.decode(),
+        Err(Error::SizeMismatch {
+            size: 0,
+            min_size: 8
+        })
+    );
+    assert!(decoder.is_empty());
+
+    decoder.push(&VRT_IF_CONTEXT_MSG[..8]);
+    assert_eq!(decoder.decode(), Ok(None));
+    assert_eq!(decoder.len(), 8);
+}
+
+#[test]
+fn test_vrl_frame_round_trip() {
+    let mut packets = [0u8; 36];
+    packets[..VRT_CONTROL_MSG.len()].copy_from_slice(VRT_CONTROL_MSG);
+    packets[VRT_CONTROL_MSG.len()..].copy_from_slice(&[0x00, 0x00, 0x00, 0x01]);
+
+    let frame = VrlFrame::new(4095, &packets);
+    let mut buf = [0u8; 48];
+    assert_eq!(frame.encode(&mut buf), Ok(48));
+    assert_eq!(buf[..8], [0x56, 0x52, 0x4c, 0x50, 0xff, 0xf0, 0x00, 0x0c]);
+
+    let (rem, res) = VrlFrame::parse(&buf).expect("failed to parse frame");
+    assert!(rem.is_empty());
+    assert_eq!(res, frame);
+    let packet_types: Vec<_> = res
+        .iter()
+        .map(|packet| packet.expect("failed to parse packet").header.packet_type)
+        .collect();
+    assert_eq!(packet_types, [PktType::Command, PktType::IfData]);
+
+    // Corrupting any byte is caught by the CRC.
+    buf[20] ^= 0x01;
+    assert!(matches!(VrlFrame::parse(&buf), Err(nom::Err::Error(_))));
+}
+
+#[test]
+fn test_vrl_frame_without_crc() {
+    let frame = VrlFrame {
+        frame_count: 1,
+        packets: &[0x00, 0x00, 0x00, 0x01],
+        crc: false,
+    };
+    let mut buf = [0u8; 16];
+    assert_eq!(frame.encode(&mut buf), Ok(16));
+    assert_eq!(buf[12..], *b"VEND");
+    assert_eq!(VrlFrame::parse(&buf), Ok((&[][..], frame)));
+
+    assert!(VrlFrame::parse(&buf[..12]).unwrap_err().is_incomplete());
+    buf[0] = b'X';
+    assert!(matches!(VrlFrame::parse(&buf), Err(nom::Err::Error(_))));
+}
+
+#[rstest]
+#[case(0, 1, Some(0))]
+#[case(1, 4, Some(2))]
+#[case(4095, 0, Some(0))]
+#[case(4094, 1, Some(2))]
+#[case(8, 7, Some(4094))]
+#[case(7, 7, None)]
+#[case(4095, 4095, None)]
+fn vrl_frames_missed(
+    #[case] previous: u16,
+    #[case] frame_count: u16,
+    #[case] expected: Option<u16>,
+) {
+    let frame = VrlFrame::new(frame_count, &[]);
+    assert_eq!(frame.frames_missed_since(previous), expected);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_owned_vrt_packet() {
+    let owned = {
+        let buf = VRT_FLEXRADIO_BROADCAST_MSG.to_vec();
+        let (_, packet) = VrtPacket::parse(&buf).expect("failed to parse packet");
+        packet.to_owned_packet()
+    };
+    let handle = std::thread::spawn(move || owned);
+    let owned = handle.join().unwrap();
+
+    assert_eq!(owned.stream_id, Some(0x800));
+    assert_eq!(owned.encode_to_vec().unwrap(), VRT_FLEXRADIO_BROADCAST_MSG);
+    let (_, packet) = VrtPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG).unwrap();
+    assert_eq!(owned.as_packet(), packet);
+    assert_eq!(OwnedVrtPacket::from(packet), owned);
+    assert_eq!(
+        OwnedVrtPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG),
+        Ok((&[][..], owned))
+    );
+}
+
+#[test]
+fn test_real_time_timestamp() {
+    let timestamp = Timestamp::real_time(Tsi::Utc, 1_700_000_000, 999_999_999_999);
+    assert_eq!(
+        timestamp.seconds_picoseconds(),
+        Some((1_700_000_000, 999_999_999_999))
+    );
+    assert_eq!(
+        timestamp.to_duration(),
+        Some(Duration::new(1_700_000_000, 999_999_999))
+    );
+
+    let later = timestamp + Duration::from_nanos(1);
+    assert_eq!(later, Timestamp::real_time(Tsi::Utc, 1_700_000_001, 999));
+    assert_eq!(later - Duration::from_nanos(1), timestamp);
+    assert_eq!(
+        later.duration_since(&timestamp),
+        Some(Duration::from_nanos(1))
+    );
+    assert_eq!(timestamp.duration_since(&later), None);
+    assert!(later > timestamp);
+
+    assert_eq!(
+        Timestamp::from_duration(Tsi::Gps, Duration::new(5, 250)),
+        Some(Timestamp::real_time(Tsi::Gps, 5, 250_000))
+    );
+    assert_eq!(
+        Timestamp::from_duration(Tsi::Gps, Duration::from_secs(1 << 32)),
+        None
+    );
+}
+
+#[test]
+fn test_timestamp_types() {
+    let sample_count = Timestamp {
+        tsi: Tsi::Utc,
+        integer: 10,
+        tsf: Tsf::SampleCount,
+        fractional: 100,
+    };
+    assert_eq!(sample_count.to_duration(), None);
+    assert_eq!(sample_count.checked_add(Duration::from_secs(1)), None);
+    let real_time = Timestamp::real_time(Tsi::Utc, 10, 100);
+    assert_eq!(sample_count.partial_cmp(&real_time), None);
+
+    let invalid = Timestamp::real_time(Tsi::Utc, 10, Timestamp::PICOSECONDS_PER_SECOND);
+    assert_eq!(invalid.seconds_picoseconds(), None);
+
+    let seconds_only = Timestamp {
+        tsi: Tsi::Other,
+        integer: 10,
+        tsf: Tsf::None,
+        fractional: 0,
+    };
+    assert_eq!(
+        seconds_only
+            .checked_add(Duration::from_secs(2))
+            .map(|t| t.integer),
+        Some(12)
+    );
+    assert_eq!(seconds_only.checked_add(Duration::from_millis(1)), None);
+}
+
+#[test]
+fn test_timestamp_gps_utc() {
+    // GPS time has been 18 seconds ahead of UTC since 2017-01-01.
+    let leap_seconds = [
+        (1_136_073_600, 14),
+        (1_230_768_000, 15),
+        (1_483_228_800, 18),
+    ];
+    let utc = Timestamp::real_time(Tsi::Utc, 1_700_000_000, 5);
+    let gps = utc
+        .utc_to_gps(&leap_seconds)
+        .expect("failed to convert to GPS");
+    assert_eq!(gps, Timestamp::real_time(Tsi::Gps, 1_384_035_218, 5));
+    assert_eq!(gps.gps_to_utc(&leap_seconds), Some(utc));
+    assert_eq!(gps.utc_to_gps(&leap_seconds), None);
+
+    let utc = Timestamp::real_time(Tsi::Utc, 1_200_000_000, 0);
+    let gps = utc.utc_to_gps(&leap_seconds).unwrap();
+    assert_eq!(gps.integer, 1_200_000_000 + 14 - Timestamp::GPS_EPOCH);
+    assert_eq!(gps.gps_to_utc(&leap_seconds), Some(utc));
+}
+
+#[test]
+fn test_vrt_packet_timestamp() {
+    let (_, packet) = VrtPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG).unwrap();
+    let timestamp = packet.timestamp().expect("missing timestamp");
+    assert_eq!(timestamp.tsi, Tsi::Utc);
+    assert_eq!(timestamp.tsf, Tsf::SampleCount);
+    assert_eq!(Some(timestamp.integer), packet.tsi);
+
+    let (_, packet) = VrtPacket::parse(VRT_CONTROL_MSG).unwrap();
+    assert_eq!(packet.timestamp(), None);
+}
+
+#[test]
+fn test_sample_count_timestamp() {
+    let sample_rate = Frequency::from_f64(48_000.0);
+    let timestamp = Timestamp {
+        tsi: Tsi::Utc,
+        integer: 100,
+        tsf: Tsf::SampleCount,
+        fractional: 47_990,
+    };
+
+    assert_eq!(
+        timestamp.sample_time(sample_rate, 0),
+        Some(Timestamp::real_time(Tsi::Utc, 100, 999_791_666_666))
+    );
+    assert_eq!(
+        timestamp.sample_time(sample_rate, 10),
+        Some(Timestamp::real_time(Tsi::Utc, 101, 0))
+    );
+    assert_eq!(timestamp.sample_count_rollover(sample_rate, 10), None);
+    assert_eq!(timestamp.sample_count_rollover(sample_rate, 11), Some(10));
+
+    let next = timestamp.advance_samples(sample_rate, 24).unwrap();
+    assert_eq!((next.integer, next.fractional), (101, 14));
+    assert_eq!(
+        next.sample_time(sample_rate, 0),
+        timestamp.sample_time(sample_rate, 24)
+    );
+
+    assert_eq!(timestamp.sample_time(Frequency::from_raw(0), 0), None);
+    let real_time = Timestamp::real_time(Tsi::Utc, 100, 0);
+    assert_eq!(real_time.sample_time(sample_rate, 0), None);
+}
+
+#[test]
+fn test_sample_count_fractional_rate() {
+    // 2.5 samples per second: counts 0, 1 and 2 fall within each second.
+    let sample_rate = Frequency::from_f64(2.5);
+    let timestamp = Timestamp {
+        tsi: Tsi::Gps,
+        integer: 7,
+        tsf: Tsf::SampleCount,
+        fractional: 2,
+    };
+    assert_eq!(
+        timestamp.sample_time(sample_rate, 0),
+        Some(Timestamp::real_time(Tsi::Gps, 7, 800_000_000_000))
+    );
+    assert_eq!(timestamp.sample_count_rollover(sample_rate, 2), Some(1));
+    let next = timestamp.advance_samples(sample_rate, 1).unwrap();
+    assert_eq!((next.integer, next.fractional), (8, 0));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_stream_tracker() {
+    let mut tracker = StreamTracker::new();
+    let (_, control) = VrtPacket::parse(VRT_CONTROL_MSG).unwrap();
+    assert_eq!(tracker.update(&control), Ok(false));
+
+    let (_, context) = VrtPacket::parse(VRT_IF_CONTEXT_MSG).unwrap();
+    assert_eq!(tracker.update(&context), Ok(true));
+
+    // Only the sample rate changes; the other fields persist.
+    let mut payload = [0u8; 12];
+    payload[..4].copy_from_slice(&0x8020_0000u32.to_be_bytes());
+    payload[4..].copy_from_slice(&Frequency::from_f64(50e6).to_be_bytes());
+    let update = VrtPacketBuilder::new(PktType::IfContext)
+        .stream_id(1)
+        .payload(&payload)
+        .build()
+        .unwrap();
+    assert_eq!(tracker.update(&update), Ok(true));
+
+    let state = *tracker.context(1, None).expect("missing stream context");
+    assert_eq!(state.sample_rate, Some(Frequency::from_f64(50e6)));
+    assert_eq!(
+        state.rf_reference_frequency,
+        Some(Frequency::from_f64(100e6))
+    );
+    assert_eq!(state.bandwidth, Some(Frequency::from_f64(20e6)));
+    assert_eq!(state.timestamp, None);
+
+    let data = VrtPacketBuilder::new(PktType::IfDataWithStream)
+        .stream_id(7)
+        .build()
+        .unwrap();
+    assert_eq!(tracker.context_for(&data), None);
+    tracker.associate(7, 1);
+    assert_eq!(tracker.context_for(&data), Some(&state));
+
+    // Unchanged context does not report a change.
+    payload[..4].copy_from_slice(&0x0020_0000u32.to_be_bytes());
+    let unchanged = VrtPacketBuilder::new(PktType::IfContext)
+        .stream_id(1)
+        .payload(&payload)
+        .build()
+        .unwrap();
+    assert_eq!(tracker.update(&unchanged), Ok(false));
+
+    // Extension Context packets carry a vendor payload rather than CIF words.
+    let vendor = VrtPacketBuilder::new(PktType::ExtContext)
+        .stream_id(1)
+        .payload(&[0xde, 0xad, 0xbe, 0xef, 0xff, 0xff, 0xff, 0xff])
+        .build()
+        .unwrap();
+    assert_eq!(tracker.update(&vendor), Ok(false));
+    assert_eq!(tracker.context(1, None), Some(&state));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_stream_tracker_classes() {
+    let class_id = |information_class_code, packet_class_code| ClassId {
+        pad_bit_count: 0,
+        oui: Oui::new(0x1c2d).unwrap(),
+        information_class_code,
+        packet_class_code,
+    };
+    let sample_rate = |rate: f64| {
+        let mut payload = [0u8; 12];
+        payload[..4].copy_from_slice(&0x8020_0000u32.to_be_bytes());
+        payload[4..].copy_from_slice(&Frequency::from_f64(rate).to_be_bytes());
+        payload
+    };
+
+    let mut tracker = StreamTracker::new();
+    let first = sample_rate(1e6);
+    let second = sample_rate(2e6);
+    for (information_class_code, payload) in [(1, &first), (2, &second)] {
+        let context = VrtPacketBuilder::new(PktType::IfContext)
+            .stream_id(1)
+            .class_id(class_id(information_class_code, 0x8000))
+            .payload(payload)
+            .build()
+            .unwrap();
+        assert_eq!(tracker.update(&context), Ok(true));
+    }
+
+    // Data packets are paired by information class; the packet class code may differ.
+    let data = |class_id| {
+        VrtPacketBuilder::new(PktType::IfDataWithStream)
+            .stream_id(1)
+            .class_id(class_id)
+            .build()
+            .unwrap()
+    };
+    let rate = |packet| {
+        tracker
+            .context_for(&packet)
+            .and_then(|state| state.sample_rate)
+    };
+    assert_eq!(rate(data(class_id(1, 1))), Some(Frequency::from_f64(1e6)));
+    assert_eq!(rate(data(class_id(2, 1))), Some(Frequency::from_f64(2e6)));
+    assert_eq!(rate(data(class_id(3, 1))), None);
+
+    // CIF3 fields persist across later context packets that omit them.
+    let mut payload = [0u8; 12];
+    payload[..4].copy_from_slice(&0x8000_0008u32.to_be_bytes());
+    payload[4..8].copy_from_slice(&0x0000_0002u32.to_be_bytes());
+    payload[8..].copy_from_slice(&42u32.to_be_bytes());
+    let network = VrtPacketBuilder::new(PktType::IfContext)
+        .stream_id(1)
+        .class_id(class_id(1, 0x8000))
+        .payload(&payload)
+        .build()
+        .unwrap();
+    assert_eq!(tracker.update(&network), Ok(true));
+    let third = sample_rate(3e6);
+    let context = VrtPacketBuilder::new(PktType::IfContext)
+        .stream_id(1)
+        .class_id(class_id(1, 0x8000))
+        .payload(&third)
+        .build()
+        .unwrap();
+    assert_eq!(tracker.update(&context), Ok(true));
+
+    let state = tracker
+        .context(1, Some(class_id(1, 0)))
+        .expect("missing stream context");
+    assert_eq!(state.sample_rate, Some(Frequency::from_f64(3e6)));
+    assert_eq!(state.cif3.network_id, Some(42));
+    assert_eq!(state.class_id, Some(class_id(1, 0x8000)));
+    assert_eq!(
+        tracker
+            .remove(1, Some(class_id(2, 0)))
+            .and_then(|state| state.sample_rate),
+        Some(Frequency::from_f64(2e6))
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_continuity_checker() {
+    let packet = |count: u8| {
+        VrtPacketBuilder::new(PktType::IfDataWithStream)
+            .stream_id(1)
+            .packet_count(count)
+            .build()
+            .unwrap()
+    };
+    let mut checker = ContinuityChecker::new();
+    let continuity: Vec<_> = [14, 15, 0, 3, 3, 1, 4]
+        .into_iter()
+        .map(|count| checker.check(&packet(count)).continuity)
+        .collect();
+    assert_eq!(
+        continuity,
+        [
+            Continuity::First,
+            Continuity::InOrder,
+            Continuity::InOrder,
+            Continuity::Dropped(2),
+            Continuity::Duplicate,
+            Continuity::Reordered,
+            Continuity::InOrder,
+        ]
+    );
+
+    let stats = checker
+        .stats(Some(1), PktType::IfDataWithStream)
+        .expect("missing stream stats");
+    assert_eq!(stats.packets, 7);
+    assert_eq!(stats.dropped, 2);
+    assert_eq!(stats.duplicated, 1);
+    assert_eq!(stats.reordered, 1);
+
+    // Context packets of the same stream are counted separately.
+    assert_eq!(checker.stats(Some(1), PktType::IfContext), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_continuity_checker_sample_loss_and_timestamps() {
+    let (_, trailer) = Trailer::parse(&[0x01, 0x00, 0x10, 0x00]).unwrap();
+    assert!(trailer.sample_loss_enable && trailer.sample_loss_indicator);
+
+    let packet = |count: u8, seconds: u32, trailer: Option<Trailer>| {
+        let builder = VrtPacketBuilder::new(PktType::IfData)
+            .packet_count(count)
+            .tsi(Tsi::Utc, seconds)
+            .tsf(Tsf::RealTime, 0);
+        match trailer {
+            Some(trailer) => builder.trailer(trailer),
+            None => builder,
+        }
+        .build()
+        .unwrap()
+    };
+    let mut checker = ContinuityChecker::new().max_timestamp_gap(Duration::from_secs(1));
+    let report = checker.check(&packet(0, 10, None));
+    assert!(!report.sample_loss && !report.timestamp_jump);
+    let report = checker.check(&packet(1, 11, Some(trailer)));
+    assert!(report.sample_loss && !report.timestamp_jump);
+    assert!(checker.check(&packet(2, 13, None)).timestamp_jump);
+    assert!(checker.check(&packet(3, 12, None)).timestamp_jump);
+
+    let stats = checker.stats(None, PktType::IfData).unwrap();
+    assert_eq!((stats.sample_loss, stats.timestamp_jumps), (1, 2));
+}
+
+#[test]
+fn test_trailer_accessors() {
+    let (_, trailer) = Trailer::parse(VRT_STATIC_TRAILER_TEST_PATTERN).unwrap();
+    assert_eq!(trailer.calibrated_time(), Some(true));
+    assert_eq!(trailer.valid_data(), None);
+    assert_eq!(trailer.reference_lock(), Some(true));
+    assert_eq!(trailer.agc(), None);
+    assert_eq!(trailer.user_defined(1), Some(true));
+    assert_eq!(trailer.user_defined(2), None);
+    assert_eq!(trailer.user_defined(5), None);
+    assert_eq!(trailer.associated_context_packet_count(), Some(0x2a));
+}
+
+#[test]
+fn test_build_trailer() {
+    let trailer = Trailer::builder()
+        .calibrated_time(true)
+        .valid_data(false)
+        .sample_loss(true)
+        .user_defined(4, true)
+        .associated_context_packet_count(0xff)
+        .build();
+    let mut buf = [0; 4];
+    assert_eq!(trailer.encode(&mut buf), Ok(4));
+    assert_eq!(buf, [0xc1, 0x18, 0x11, 0xff]);
+    assert_eq!(Trailer::parse(&buf), Ok((&[][..], trailer)));
+    assert_eq!(trailer.associated_context_packet_count(), Some(0x7f));
+    assert_eq!(Trailer::builder().build(), Trailer::default());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_socket_round_trip() {
+    let mut receiver = VrtSocket::bind("127.0.0.1:0").unwrap();
+    let mut sender = VrtSocket::bind("127.0.0.1:0").unwrap();
+    let target = receiver.get_ref().local_addr().unwrap();
+    let source = sender.get_ref().local_addr().unwrap();
+
+    let (_, packet) = VrtPacket::parse(VRT_IF_CONTEXT_MSG).unwrap();
+    sender.send_packet(&packet, target).unwrap();
+    assert_eq!(receiver.recv_packet(), Ok((packet, source)));
+    assert_eq!(receiver.source_addr(), Some(source));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_socket_bind_error() {
+    let socket = VrtSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.get_ref().local_addr().unwrap();
+    let error = VrtSocket::bind(addr).unwrap_err();
+    let Error::Io { kind, os_error } = error else {
+        panic!("unexpected error {error:?}");
+    };
+    assert_eq!(kind, std::io::ErrorKind::AddrInUse);
+    let os_error = os_error.expect("missing OS error code");
+    assert!(error
+        .to_string()
+        .ends_with(&format!("(os error {os_error})")));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_socket_datagrams() {
+    let mut receiver = VrtSocket::bind("127.0.0.1:0").unwrap();
+    let sender = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let target = receiver.get_ref().local_addr().unwrap();
+
+    let mut datagram = VRT_FLEXRADIO_BROADCAST_MSG.to_vec();
+    datagram.extend_from_slice(VRT_CONTROL_MSG);
+    sender.send_to(&datagram, target).unwrap();
+    sender.send_to(&[], target).unwrap();
+    sender.send_to(&[0x80, 0x00, 0x00, 0x01], target).unwrap();
+    sender.send_to(VRT_IF_CONTEXT_MSG, target).unwrap();
+
+    let mut packet_types = Vec::new();
+    for _ in 0..4 {
+        let packet = receiver.recv_packet();
+        packet_types.push(packet.map(|(packet, _)| packet.header.packet_type));
+    }
+    assert_eq!(
+        packet_types,
+        [
+            Ok(PktType::ExtDataWithStream),
+            Ok(PktType::Command),
+            Err(Error::UnsupportedPacketType(8)),
+            Ok(PktType::IfContext)
+        ]
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_socket_options() {
+    use std::net::Ipv4Addr;
+
+    let socket = VrtSocket::bind("0.0.0.0:0").unwrap();
+    socket.set_recv_buffer_size(1 << 18).unwrap();
+    assert!(socket.recv_buffer_size().unwrap() >= 1 << 18);
+
+    let group = Ipv4Addr::new(239, 255, 49, 1);
+    if socket
+        .join_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)
+        .is_ok()
+    {
+        socket
+            .leave_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)
+            .unwrap();
+    }
+    assert!(socket
+        .leave_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)
+        .is_err());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_udp_stream_round_trip() {
+    use futures::{SinkExt, StreamExt};
+
+    let mut receiver = VrtUdpStream::bind("127.0.0.1:0").await.unwrap();
+    let mut sender = VrtUdpStream::bind("127.0.0.1:0").await.unwrap();
+    let target = receiver.get_ref().local_addr().unwrap();
+    let source = sender.get_ref().local_addr().unwrap();
+
+    let (_, packet) = VrtPacket::parse(VRT_IF_CONTEXT_MSG).unwrap();
+    sender.send((packet.clone(), target)).await.unwrap();
+    sender
+        .send((packet.to_owned_packet(), target))
+        .await
+        .unwrap();
+
+    assert_eq!(receiver.next().await, Some(Ok(packet.to_owned_packet())));
+    assert_eq!(receiver.source_addr(), Some(source));
+    assert_eq!(
+        receiver.recv_from().await,
+        Ok((packet.to_owned_packet(), source))
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_udp_stream_datagrams() {
+    use futures::StreamExt;
+
+    let mut receiver = VrtUdpStream::bind("127.0.0.1:0").await.unwrap();
+    let sender = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let target = receiver.get_ref().local_addr().unwrap();
+
+    let mut datagram = VRT_FLEXRADIO_BROADCAST_MSG.to_vec();
+    datagram.extend_from_slice(VRT_CONTROL_MSG);
+    sender.send_to(&datagram, target).await.unwrap();
+    sender
+        .send_to(&[0x80, 0x00, 0x00, 0x01], target)
+        .await
+        .unwrap();
+    sender.send_to(VRT_IF_CONTEXT_MSG, target).await.unwrap();
+
+    let mut packet_types = Vec::new();
+    for _ in 0..4 {
+        let packet = receiver.next().await.unwrap();
+        packet_types.push(packet.map(|packet| packet.header.packet_type));
+    }
+    assert_eq!(
+        packet_types,
+        [
+            Ok(PktType::ExtDataWithStream),
+            Ok(PktType::Command),
+            Err(Error::UnsupportedPacketType(8)),
+            Ok(PktType::IfContext)
+        ]
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_udp_stream_buffer_sizes() {
+    let stream = VrtUdpStream::bind("127.0.0.1:0").await.unwrap();
+    stream.set_recv_buffer_size(1 << 18).unwrap();
+    assert!(stream.recv_buffer_size().unwrap() >= 1 << 18);
+
+    let mut stream = stream.with_buffer_size(16);
+    let sender = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let target = stream.get_ref().local_addr().unwrap();
+    sender.send_to(VRT_IF_CONTEXT_MSG, target).await.unwrap();
+    assert_eq!(
+        stream.recv_from().await,
+        Err(Error::Truncated { needed: 84 })
+    );
+}
+
+/// Ethernet frame carrying an IPv4 UDP datagram from 192.168.1.2:50000 to `port`.
+#[cfg(feature = "alloc")]
+fn udp_frame(port: u16, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0xff; 12];
+    frame.extend_from_slice(&[0x81, 0x00, 0x00, 0x05, 0x08, 0x00]);
+    frame.extend_from_slice(&[0x45, 0x00]);
+    frame.extend_from_slice(&(28 + payload.len() as u16).to_be_bytes());
+    frame.extend_from_slice(&[
+        0, 0, 0x40, 0, 64, 17, 0, 0, 192, 168, 1, 2, 192, 168, 1, 255,
+    ]);
+    frame.extend_from_slice(&[0xc3, 0x50]);
+    frame.extend_from_slice(&port.to_be_bytes());
+    frame.extend_from_slice(&(8 + payload.len() as u16).to_be_bytes());
+    frame.extend_from_slice(&[0, 0]);
+    frame.extend_from_slice(payload);
+    frame
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_pcap_vrt_packets() {
+    let mut datagram = VRT_FLEXRADIO_BROADCAST_MSG.to_vec();
+    datagram.extend_from_slice(VRT_CONTROL_MSG);
+    let arp = [[0xff; 12].as_slice(), &[0x08, 0x06], &[0; 28]].concat();
+
+    let mut file = vec![0xd4, 0xc3, 0xb2, 0xa1, 2, 0, 4, 0];
+    file.extend_from_slice(&[0; 8]);
+    file.extend_from_slice(&[0xff, 0xff, 0, 0, 1, 0, 0, 0]);
+    for (seconds, frame) in [
+        (10u32, udp_frame(4992, &datagram)),
+        (11, arp),
+        (12, udp_frame(4991, VRT_IF_CONTEXT_MSG)),
+        (13, udp_frame(4992, &[0x80, 0x00, 0x00, 0x01])),
+    ] {
+        file.extend_from_slice(&seconds.to_le_bytes());
+        file.extend_from_slice(&250_000u32.to_le_bytes());
+        file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        file.extend_from_slice(&frame);
+    }
+
+    let capture = Capture::new(&file).unwrap();
+    assert_eq!(capture.frames().count(), 4);
+    let payload = capture.payloads().next().unwrap().unwrap();
+    assert_eq!(payload.transport, Transport::Udp);
+    assert_eq!(payload.source, "192.168.1.2:50000".parse().unwrap());
+    assert_eq!(payload.destination, "192.168.1.255:4992".parse().unwrap());
+    assert_eq!(payload.data, datagram);
+
+    let packets: Vec<_> = capture.vrt_packets().port(4992).collect();
+    assert_eq!(packets.len(), 3);
+    let (timestamp, packet) = packets[1].clone().unwrap();
+    assert_eq!(timestamp, Duration::new(10, 250_000_000));
+    assert_eq!(packet.header.packet_type, PktType::Command);
+    assert!(packets[2].is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_pcapng_payloads() {
+    fn block(block_type: u32, body: &[u8]) -> Vec<u8> {
+        let len = (12 + body.len() as u32).to_le_bytes();
+        [&block_type.to_le_bytes(), &len, body, &len].concat()
+    }
+    let mut segment = vec![0x13, 0x80, 0xc3, 0x50, 0, 0, 0, 1, 0, 0, 0, 0, 0x50, 0x18];
+    segment.extend_from_slice(&[0; 6]);
+    segment.extend_from_slice(VRT_IF_CONTEXT_MSG);
+    let mut frame = vec![0; 12];
+    frame.extend_from_slice(&[0x86, 0xdd, 0x60, 0, 0, 0]);
+    frame.extend_from_slice(&(segment.len() as u16).to_be_bytes());
+    frame.extend_from_slice(&[6, 64]);
+    frame.extend_from_slice(&[0; 15]);
+    frame.push(1);
+    frame.extend_from_slice(&[0; 15]);
+    frame.push(2);
+    frame.extend_from_slice(&segment);
+    frame.extend_from_slice(&[0; 2]);
+
+    let mut file = block(
+        0x0a0d_0d0a,
+        &[
+            0x4d, 0x3c, 0x2b, 0x1a, 1, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ],
+    );
+    file.extend(block(
+        1,
+        &[1, 0, 0, 0, 0, 0, 0, 0, 9, 0, 1, 0, 9, 0, 0, 0, 0, 0, 0, 0],
+    ));
+    let mut epb = Vec::new();
+    for word in [
+        0,
+        0,
+        1_500_000_001,
+        frame.len() as u32 - 2,
+        frame.len() as u32,
+    ] {
+        epb.extend_from_slice(&word.to_le_bytes());
+    }
+    epb.extend_from_slice(&frame[..frame.len() - 2]);
+    epb.resize(epb.len().next_multiple_of(4), 0);
+    file.extend(block(6, &epb));
+    file.extend(block(6, &[1, 0, 0, 0]));
+
+    let capture = Capture::new(&file).unwrap();
+    let mut payloads = capture.payloads().port(50000);
+    let payload = payloads.next().unwrap().unwrap();
+    assert_eq!(payload.timestamp, Duration::new(1, 500_000_001));
+    assert_eq!(payload.transport, Transport::Tcp);
+    assert_eq!(payload.source, "[::1]:4992".parse().unwrap());
+    assert_eq!(payload.destination, "[::2]:50000".parse().unwrap());
+    assert_eq!(payload.data, VRT_IF_CONTEXT_MSG);
+    assert!(payloads.next().unwrap().is_err());
+    assert!(payloads.next().is_none());
+    let packets: Vec<_> = capture.vrt_packets().collect();
+    assert!(matches!(packets[..], [Err(_)]));
+
+    assert_eq!(
+        Capture::new(&[0, 1, 2, 3]).unwrap_err(),
+        Error::InvalidField {
+            field: "capture header",
+            offset: 0
+        }
+    );
+}
+
+#[rstest]
+#[case::zero_size(&[0x10, 0x00, 0x00, 0x00])]
+#[case::stream_id(&[0x10, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01])]
+#[case::class_id(&[0x18, 0x00, 0x00, 0x02, 0, 0, 0, 1, 0, 0, 0x1c, 0x2d, 0, 1, 0, 2])]
+#[case::trailer(&[0x04, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00])]
+#[case::timestamps(&[
+    0x60, 0x39, 0x00, 0x00, 0xcb, 0x00, 0x00, 0x01, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0xa8, 0x08, 0x00, 0x00, 0x00, 0x00,
+])]
+fn parse_packet_size_too_small(#[case] input: &[u8]) {
+    assert_eq!(
+        VrtPacket::parse(input),
+        Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify
+        )))
+    );
+}
+
+#[test]
+fn test_parse_packet_truncated() {
+    assert_eq!(
+        VrtPacket::parse(&VRT_IF_CONTEXT_MSG[..40]),
+        Err(nom::Err::Incomplete(nom::Needed::new(60)))
+    );
+}
+
+#[rstest]
+#[case::truncated(&VRT_IF_CONTEXT_MSG[..40], Error::Truncated { needed: 60 })]
+#[case::header(&[0x10, 0x00], Error::Truncated { needed: 2 })]
+#[case::packet_type(&[0x80, 0x00, 0x00, 0x01], Error::UnsupportedPacketType(8))]
+#[case::packet_size(
+    &[0x18, 0x00, 0x00, 0x02, 0, 0, 0, 1, 0, 0, 0x1c, 0x2d, 0, 1, 0, 2],
+    Error::SizeMismatch { size: 8, min_size: 16 }
+)]
+fn packet_from_bytes_errors(#[case] input: &[u8], #[case] expected: Error) {
+    assert_eq!(VrtPacket::from_bytes(input), Err(expected));
+}
+
+#[test]
+fn test_packet_from_bytes() {
+    let mut bytes = VRT_CONTROL_MSG.to_vec();
+    bytes.extend_from_slice(&[0xff; 4]);
+    let (packet, len) = VrtPacket::from_bytes(&bytes).unwrap();
+    assert_eq!(len, VRT_CONTROL_MSG.len());
+    assert_eq!(packet.header.packet_type, PktType::Command);
+
+    let (packet, len) = CommandPacket::from_bytes(&bytes).unwrap();
+    assert_eq!(len, VRT_CONTROL_MSG.len());
+    assert_eq!(packet.stream_id, 1);
+    assert_eq!(
+        ContextPacket::from_bytes(&bytes).unwrap_err(),
+        Error::UnsupportedPacketType(6)
+    );
+    assert_eq!(
+        CommandPacket::from_bytes(VRT_IF_CONTEXT_MSG).unwrap_err(),
+        Error::UnsupportedPacketType(4)
+    );
+}
+
+#[test]
+fn test_context_rejects_ext_context() {
+    // Extension Context packet whose payload is defined by its packet class, not by CIF words.
+    let bytes = [
+        0x50, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0xde, 0xad, 0xbe, 0xef,
+    ];
+    assert_eq!(
+        ContextPacket::from_bytes(&bytes).unwrap_err(),
+        Error::UnsupportedPacketType(5)
+    );
+    assert!(ContextPacket::parse(&bytes).is_err());
+}
+
+#[test]
+fn test_command_rejects_ext_command() {
+    // Extension Command packet whose payload is defined by its packet class, not by the CAM.
+    let bytes = [
+        0x70, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0xde, 0xad, 0xbe, 0xef,
+    ];
+    assert_eq!(
+        CommandPacket::from_bytes(&bytes).unwrap_err(),
+        Error::UnsupportedPacketType(7)
+    );
+    assert!(CommandPacket::parse(&bytes).is_err());
+}
+
+#[test]
+fn test_context_field_attributes_unsupported() {
+    // CIF0 enables CIF7, which requests the current value of each field.
+    let bytes = [
+        0x40, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x80, 0x80, 0x00, 0x00,
+        0x00,
+    ];
+    assert_eq!(
+        ContextPacket::from_bytes(&bytes).unwrap_err(),
+        Error::FieldAttributes
+    );
+}
+
+#[test]
+fn test_context_from_bytes_invalid_field() {
+    // CIF0 indicates a bandwidth field, but the payload ends after the CIF0 word.
+    let bytes = [
+        0x40, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x20, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(
+        ContextPacket::from_bytes(&bytes).unwrap_err(),
+        Error::InvalidField {
+            field: "context fields",
+            offset: 12
+        }
+    );
+}
+
+#[test]
+fn test_vrl_frame_from_bytes() {
+    let frame = VrlFrame::new(1, VRT_CONTROL_MSG);
+    let mut buf = [0u8; 44];
+    assert_eq!(frame.encode(&mut buf), Ok(44));
+    assert_eq!(VrlFrame::from_bytes(&buf), Ok((frame, 44)));
+    assert_eq!(
+        VrlFrame::from_bytes(&buf[..20]),
+        Err(Error::Truncated { needed: 24 })
+    );
+
+    let computed = u32::from_be_bytes(buf[40..].try_into().unwrap());
+    buf[40] ^= 0xff;
+    let received = u32::from_be_bytes(buf[40..].try_into().unwrap());
+    assert_eq!(
+        VrlFrame::from_bytes(&buf),
+        Err(Error::CrcMismatch { received, computed })
+    );
+
+    buf[0] = 0;
+    assert_eq!(
+        VrlFrame::from_bytes(&buf),
+        Err(Error::InvalidField {
+            field: "alignment word",
+            offset: 0
+        })
+    );
+    assert_eq!(
+        VrlFrame::from_bytes(&[0x56, 0x52, 0x4c, 0x50, 0x00, 0x00, 0x00, 0x02]),
+        Err(Error::SizeMismatch {
+            size: 8,
+            min_size: 12
+        })
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_validate_valid_packets() {
+    for packet in [
+        VRT_FLEXRADIO_BROADCAST_MSG,
+        VRT_IF_CONTEXT_MSG,
+        VRT_CONTROL_MSG,
+        VRT_VALIDATION_ACK_MSG,
+    ] {
+        assert_eq!(VrtPacket::validate_bytes(packet), []);
+    }
+
+    // Extension packets carry a vendor payload rather than CIF words or a CAM field.
+    for packet_type in [0x50, 0x70] {
+        let mut packet = [
+            0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0xde, 0xad, 0xbe, 0xef,
+        ];
+        packet[0] = packet_type;
+        assert_eq!(VrtPacket::validate_bytes(&packet), []);
+    }
+
+    assert_eq!(
+        VrtPacket::validate_bytes(VRT_IF_CONTEXT_V49_2_MSG),
+        [Finding {
+            severity: Severity::Warning,
+            rule: Rule::V49_2FieldsWithoutIndicator
+        }]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[rstest]
+#[case::trailer_on_context(
+    VRT_IF_CONTEXT_MSG,
+    0,
+    0x44,
+    Severity::Error,
+    Rule::TrailerOnNonDataPacket
+)]
+#[case::reserved_header_bits(VRT_CONTROL_MSG, 0, 0x62, Severity::Error, Rule::ReservedBits { field: "header", bits: 0x0200_0000 })]
+#[case::reserved_class_id_bits(VRT_FLEXRADIO_BROADCAST_MSG, 8, 0x04, Severity::Error, Rule::ReservedBits { field: "class id", bits: 0x0400_0000 })]
+#[case::reserved_cif0_bits(VRT_IF_CONTEXT_MSG, 23, 0x01, Severity::Error, Rule::ReservedBits { field: "cif0", bits: 0x01 })]
+#[case::reserved_cam_bits(VRT_CONTROL_MSG, 11, 0x01, Severity::Error, Rule::ReservedBits { field: "cam", bits: 0x01 })]
+#[case::tsf_out_of_range(VRT_IF_CONTEXT_MSG, 12, 0xff, Severity::Error, Rule::OutOfRange { field: "fractional-seconds timestamp" })]
+#[case::timing_control_without_timestamp(
+    VRT_CONTROL_MSG,
+    10,
+    0x10,
+    Severity::Error,
+    Rule::TimingControlWithoutTimestamp
+)]
+#[case::multiple_acknowledge_types(
+    VRT_VALIDATION_ACK_MSG,
+    9,
+    0x1a,
+    Severity::Error,
+    Rule::MultipleAcknowledgeTypes
+)]
+fn validate_violation(
+    #[case] packet: &[u8],
+    #[case] offset: usize,
+    #[case] value: u8,
+    #[case] severity: Severity,
+    #[case] rule: Rule,
+) {
+    let mut bytes = packet.to_vec();
+    bytes[offset] = value;
+    assert_eq!(
+        VrtPacket::validate_bytes(&bytes),
+        [Finding { severity, rule }]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_validate_packet_size() {
+    let mut bytes = VRT_FLEXRADIO_BROADCAST_MSG.to_vec();
+    bytes.extend_from_slice(&[0; 4]);
+    assert_eq!(
+        VrtPacket::validate_bytes(&bytes),
+        [Finding {
+            severity: Severity::Warning,
+            rule: Rule::PacketSizeMismatch {
+                packet_size: 552,
+                buffer_len: 556
+            }
+        }]
+    );
+    assert_eq!(
+        VrtPacket::validate_bytes(&bytes[..8]),
+        [Finding {
+            severity: Severity::Error,
+            rule: Rule::Unparseable(Error::Truncated { needed: 544 })
+        }]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_validate_reports_every_violation() {
+    let packet = VrtPacketBuilder::new(PktType::IfContext)
+        .stream_id(1)
+        .class_id(ClassId {
+            pad_bit_count: 1,
+            oui: Oui::new(0x1c2d).unwrap(),
+            information_class_code: 0,
+            packet_class_code: 0,
+        })
+        .tsf(Tsf::RealTime, Timestamp::PICOSECONDS_PER_SECOND)
+        .payload(&[0; 4])
+        .build()
+        .unwrap();
+    let findings = [
+        Finding {
+            severity: Severity::Warning,
+            rule: Rule::RealTimeTsfWithoutTsi,
+        },
+        Finding {
+            severity: Severity::Error,
+            rule: Rule::OutOfRange {
+                field: "fractional-seconds timestamp",
+            },
+        },
+        Finding {
+            severity: Severity::Error,
+            rule: Rule::PadBitCount(1),
+        },
+    ];
+    assert_eq!(packet.validate(), findings);
+    assert_eq!(
+        ContextPacket::try_from(packet).unwrap().validate(),
+        findings
+    );
+}
+
+#[rstest]
+#[case(PackingMethod::LinkEfficient, 12, 64, 5)]
+#[case(PackingMethod::ProcessingEfficient, 12, 64, 4)]
+#[case(PackingMethod::ProcessingEfficient, 12, 52, 3)]
+#[case(PackingMethod::ProcessingEfficient, 48, 112, 2)]
+#[case(PackingMethod::ProcessingEfficient, 48, 111, 1)]
+fn payload_item_count(
+    #[case] packing_method: PackingMethod,
+    #[case] field_size: u8,
+    #[case] bits: usize,
+    #[case] expected: usize,
+) {
+    let format = payload_format(
+        packing_method,
+        RealComplexType::Real,
+        DataItemFormat::SignedFixed,
+        field_size,
+        field_size,
+    );
+    assert_eq!(format.item_count(bits), expected);
+}
+
+#[test]
+fn test_samples_exclude_pad_bits() {
+    let format = payload_format(
+        PackingMethod::LinkEfficient,
+        RealComplexType::Real,
+        DataItemFormat::SignedFixed,
+        12,
+        12,
+    );
+    let samples: [i16; 4] = [0x123, -0x800, 0x7ff, 0x001];
+    let mut payload = [0; 8];
+    format.pack(&samples, &mut payload).unwrap();
+
+    let class_id = ClassId {
+        pad_bit_count: 16,
+        oui: Oui::new(0x1c2d).unwrap(),
+        information_class_code: 0,
+        packet_class_code: 0,
+    };
+    let packet = VrtPacket::builder(PktType::IfDataWithStream)
+        .stream_id(1)
+        .class_id(class_id)
+        .payload(&payload)
+        .build()
+        .unwrap();
+    assert_eq!(packet.pad_bit_count(), 16);
+    assert_eq!(packet.payload_bits(), 48);
+    assert_eq!(packet.sample_count::<i16>(&format), 4);
+    assert_eq!(packet.samples::<i16>(&format).collect::<Vec<_>>(), samples);
+
+    let mut buf = [0; 24];
+    let len = packet.encode(&mut buf).unwrap();
+    let (_, packet) = VrtPacket::parse(&buf[..len]).unwrap();
+    assert_eq!(packet.class_id, Some(class_id));
+    assert_eq!(Oui::new(0x0100_0000), None);
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq)]
+enum VendorMessage {
+    Discovery(Vec<(String, String)>),
+}
+
+#[cfg(feature = "alloc")]
+struct DiscoveryDecoder;
+
+#[cfg(feature = "alloc")]
+impl ClassDecoder for DiscoveryDecoder {
+    type Message = VendorMessage;
+
+    fn decode(&self, packet: &VrtPacket<'_>) -> Result<VendorMessage, Error> {
+        let text = std::str::from_utf8(packet.payload).map_err(|e| Error::InvalidField {
+            field: "discovery payload",
+            offset: e.valid_up_to(),
+        })?;
+        let pairs = text
+            .trim_end_matches('\0')
+            .split(' ')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        Ok(VendorMessage::Discovery(pairs))
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_class_registry_dispatch() {
+    let flex_radio = Oui::new(0x1c2d).unwrap();
+    let mut registry = ClassRegistry::new();
+    assert!(registry.is_empty());
+    assert!(registry
+        .register(flex_radio, 0x534c, 0xffff, DiscoveryDecoder)
+        .is_none());
+    assert_eq!(registry.len(), 1);
+
+    let (_, packet) = VrtPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG).unwrap();
+    let Some(Ok(VendorMessage::Discovery(pairs))) = registry.decode(&packet) else {
+        panic!("discovery packet not decoded");
+    };
+    assert_eq!(pairs[1], ("model".to_string(), "FLEX-6700".to_string()));
+
+    let (_, context) = VrtPacket::parse(VRT_IF_CONTEXT_MSG).unwrap();
+    assert!(registry.decode(&context).is_none());
+
+    let mut other = packet.clone();
+    other.class_id = other.class_id.map(|class_id| ClassId {
+        packet_class_code: 0,
+        ..class_id
+    });
+    assert!(registry.decode(&other).is_none());
+
+    let mut payload = packet.payload.to_vec();
+    payload[4] = 0xff;
+    other = VrtPacket {
+        payload: &payload,
+        ..packet
+    };
+    assert_eq!(
+        registry.decode(&other),
+        Some(Err(Error::InvalidField {
+            field: "discovery payload",
+            offset: 4
+        }))
+    );
+
+    assert!(registry.unregister(flex_radio, 0x534c, 0xffff).is_some());
+    assert!(registry.is_empty());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_class_registry_shared_between_threads() {
+    let mut registry = ClassRegistry::new();
+    registry.register(Oui::new(0x1c2d).unwrap(), 0x534c, 0xffff, DiscoveryDecoder);
+
+    let registry = std::sync::Arc::new(registry);
+    let shared = std::sync::Arc::clone(&registry);
+    let decoded = std::thread::spawn(move || {
+        let (_, packet) = VrtPacket::parse(VRT_FLEXRADIO_BROADCAST_MSG).unwrap();
+        shared
+            .decode(&packet)
+            .is_some_and(|message| message.is_ok())
+    })
+    .join()
+    .unwrap();
+    assert!(decoded);
+}